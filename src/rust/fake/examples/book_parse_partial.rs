@@ -0,0 +1,19 @@
+//! Test double that exports `from_utf8` and `dispose` only, so loading it fails with
+//! `MissingSymbol` for `book_info`. `cargo test` builds it next to the test binaries.
+
+use std::{
+    os::raw::{c_uchar, c_uint, c_void},
+    ptr,
+};
+
+/// # Safety
+/// It is never called, the library cannot be loaded.
+#[no_mangle]
+pub unsafe extern "C" fn from_utf8(_: *mut c_uchar, _: c_uint) -> *mut c_void {
+    ptr::null_mut()
+}
+
+/// # Safety
+/// It is never called, the library cannot be loaded.
+#[no_mangle]
+pub unsafe extern "C" fn dispose(_: *mut c_void) {}
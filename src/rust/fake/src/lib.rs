@@ -0,0 +1,258 @@
+//! Test double of the native `book_parse` library.
+//!
+//! It exports the same C ABI as `lib/book_parse.h` and splits a text in a simple,
+//! deterministic way: every non-empty line is a paragraph, a sentence ends with
+//! `.`, `!` or `?` followed by a space.
+//!
+//! Faults are injected by directive lines at the beginning of the text, they are not
+//! a part of the parsed book:
+//!
+//! * `#!null` - `from_utf8` returns a null book;
+//! * `#!empty` - the book reports zero size;
+//! * `#!invalid-utf8` - texts are written with an invalid UTF-8 byte at the beginning;
+//! * `#!short-write` - texts are written without their last byte;
+//! * `#!long-write` - texts are written with an extra byte after the advertised size
+//!   (it overflows a buffer that has no extra space);
+//! * `#!wrong-symbols` - sizes report one symbol more than the text has;
+//! * `#!wrong-p-index` - sentences report the next paragraph as their own one;
+//! * `#!slow` - `from_utf8` takes half a second.
+
+use std::{
+    os::raw::{c_uchar, c_uint, c_void},
+    ptr, slice, thread,
+    time::Duration,
+};
+
+use bpw::{BookInfo, ParagraphId, ParagraphInfo, SentenceId, SentenceInfo, StringSize};
+
+const DIRECTIVE: &str = "#!";
+
+#[derive(Debug, Default)]
+struct Faults {
+    null: bool,
+    empty: bool,
+    invalid_utf8: bool,
+    short_write: bool,
+    long_write: bool,
+    wrong_symbols: bool,
+    wrong_p_index: bool,
+    slow: bool,
+}
+
+impl Faults {
+    /// Reads directives and returns the rest of the text
+    fn parse(mut text: &str) -> (Self, &str) {
+        let mut faults = Faults::default();
+
+        while let Some(rest) = text.strip_prefix(DIRECTIVE) {
+            let (line, rest) = rest.split_once('\n').unwrap_or((rest, ""));
+            match line.trim() {
+                "null" => faults.null = true,
+                "empty" => faults.empty = true,
+                "invalid-utf8" => faults.invalid_utf8 = true,
+                "short-write" => faults.short_write = true,
+                "long-write" => faults.long_write = true,
+                "wrong-symbols" => faults.wrong_symbols = true,
+                "wrong-p-index" => faults.wrong_p_index = true,
+                "slow" => faults.slow = true,
+                _ => (),
+            }
+            text = rest;
+        }
+
+        (faults, text)
+    }
+
+    fn size(&self, text: &str) -> StringSize {
+        let mut size = StringSize::from(text);
+        if self.wrong_symbols {
+            size.symbols += 1;
+        }
+        size
+    }
+}
+
+#[derive(Debug)]
+struct FakeBook {
+    faults: Faults,
+    info: BookInfo,
+    paragraphes: Vec<(ParagraphInfo, String)>,
+    sentences: Vec<(SentenceInfo, String)>,
+}
+
+impl FakeBook {
+    fn parse(text: &str) -> Self {
+        let (faults, text) = Faults::parse(text);
+        let mut paragraphes = vec![];
+        let mut sentences = vec![];
+
+        for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+            let index = ParagraphId::from(paragraphes.len() as c_uint);
+            let sentence_first = SentenceId::from(sentences.len() as c_uint);
+            let parts = split_sentences(line);
+
+            for (s_number, part) in parts.iter().enumerate() {
+                let p_index = match faults.wrong_p_index {
+                    true => c_uint::from(index) + 1,
+                    false => c_uint::from(index),
+                };
+                let info = SentenceInfo {
+                    index: SentenceId::from(sentences.len() as c_uint),
+                    s_number: s_number as c_uint,
+                    p_index: p_index.into(),
+                    size: faults.size(part),
+                };
+                sentences.push((info, String::from(*part)));
+            }
+
+            let text = parts.join(" ");
+            let info = ParagraphInfo {
+                index,
+                sentence_first,
+                sentences: parts.len() as c_uint,
+                size: faults.size(&text),
+            };
+            paragraphes.push((info, text));
+        }
+
+        let size = match faults.empty {
+            true => StringSize::default(),
+            false => faults.size(
+                &paragraphes
+                    .iter()
+                    .map(|p| p.1.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+        };
+
+        let info = BookInfo {
+            paragraphes: paragraphes.len() as c_uint,
+            sentences: sentences.len() as c_uint,
+            size,
+        };
+
+        Self {
+            faults,
+            info,
+            paragraphes,
+            sentences,
+        }
+    }
+
+    /// Writes `text` into `buff` as a native library would do, but with injected faults
+    unsafe fn write(&self, text: &str, buff: *mut c_uchar) {
+        let mut bytes = text.as_bytes().to_vec();
+        if self.faults.invalid_utf8 && !bytes.is_empty() {
+            bytes[0] = 0xff;
+        }
+        if self.faults.short_write {
+            bytes.pop();
+        }
+        if self.faults.long_write {
+            bytes.push(b' ');
+        }
+
+        ptr::copy_nonoverlapping(bytes.as_ptr(), buff, bytes.len());
+    }
+}
+
+fn split_sentences(line: &str) -> Vec<&str> {
+    let mut sentences = vec![];
+    let mut start = 0;
+
+    for (i, c) in line.char_indices() {
+        let end = i + c.len_utf8();
+        if matches!(c, '.' | '!' | '?') && line[end..].starts_with(' ') {
+            sentences.push(line[start..end].trim());
+            start = end;
+        }
+    }
+
+    if !line[start..].trim().is_empty() {
+        sentences.push(line[start..].trim());
+    }
+
+    sentences
+}
+
+unsafe fn book<'a>(book: *mut c_void) -> &'a FakeBook {
+    &*(book as *const FakeBook)
+}
+
+/// # Safety
+/// `p` must point to `len` bytes of UTF-8 text.
+#[no_mangle]
+pub unsafe extern "C" fn from_utf8(p: *mut c_uchar, len: c_uint) -> *mut c_void {
+    let text = match std::str::from_utf8(slice::from_raw_parts(p, len as usize)) {
+        Ok(text) => text,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    let book = FakeBook::parse(text);
+    if book.faults.slow {
+        thread::sleep(Duration::from_millis(500));
+    }
+    match book.faults.null {
+        true => ptr::null_mut(),
+        false => Box::into_raw(Box::new(book)) as *mut c_void,
+    }
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet.
+#[no_mangle]
+pub unsafe extern "C" fn dispose(book: *mut c_void) {
+    drop(Box::from_raw(book as *mut FakeBook))
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet.
+#[no_mangle]
+pub unsafe extern "C" fn book_info(b: *mut c_void) -> BookInfo {
+    book(b).info
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet.
+#[no_mangle]
+pub unsafe extern "C" fn paragraph_info(b: *mut c_void, index: ParagraphId) -> ParagraphInfo {
+    book(b)
+        .paragraphes
+        .get(c_uint::from(index) as usize)
+        .map(|p| p.0)
+        .unwrap_or_default()
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet,
+/// `buff` must be large enough for the paragraph text.
+#[no_mangle]
+pub unsafe extern "C" fn paragraph_text(b: *mut c_void, index: ParagraphId, buff: *mut c_uchar) {
+    let b = book(b);
+    if let Some((_, text)) = b.paragraphes.get(c_uint::from(index) as usize) {
+        b.write(text, buff)
+    }
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sentence_info(b: *mut c_void, index: SentenceId) -> SentenceInfo {
+    book(b)
+        .sentences
+        .get(c_uint::from(index) as usize)
+        .map(|s| s.0)
+        .unwrap_or_default()
+}
+
+/// # Safety
+/// `book` must be returned by `from_utf8` and not disposed yet,
+/// `buff` must be large enough for the sentence text.
+#[no_mangle]
+pub unsafe extern "C" fn sentence_text(b: *mut c_void, index: SentenceId, buff: *mut c_uchar) {
+    let b = book(b);
+    if let Some((_, text)) = b.sentences.get(c_uint::from(index) as usize) {
+        b.write(text, buff)
+    }
+}
@@ -0,0 +1,121 @@
+//! Covers async parsing over the test double of the native library.
+
+mod common;
+
+use std::{
+    env, fs,
+    ops::ControlFlow,
+    process,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use bpw::{Book, CancelToken, ChunkedParser, Error, Progress};
+use common::{assert_same, setup, TEXT};
+use futures_util::{Stream, StreamExt};
+
+#[tokio::test]
+async fn parse_async() {
+    setup();
+    let book = Book::parse_async(TEXT, &CancelToken::new()).await.unwrap();
+    assert_same(&book.book(), &Book::from_utf8(TEXT).unwrap());
+
+    let result = Book::parse_async(" \n", &CancelToken::new()).await;
+    assert_eq!(result.unwrap_err(), Error::EmptyInput);
+}
+
+#[tokio::test]
+async fn from_path_async() {
+    setup();
+    let path = env::temp_dir().join(format!("book-parse-async-{}.txt", process::id()));
+    fs::write(&path, TEXT).unwrap();
+
+    let book = Book::from_path_async(&path, &CancelToken::new()).await;
+    let expected = Book::from_utf8(TEXT).unwrap();
+    let book = book.unwrap();
+    assert_eq!(book.text(), TEXT);
+    assert_eq!(book.info(), expected.info());
+    for (s, e) in book.sentences().zip(expected.sentences()) {
+        assert_eq!(s.text(), e.text());
+        assert_eq!(s.span(), e.span());
+    }
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let result = Book::from_path_async(&path, &cancel).await;
+    assert_eq!(result.unwrap_err(), Error::Cancelled);
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn chunked_async() {
+    setup();
+    let reported = Arc::new(Mutex::new(vec![]));
+    let progress = Arc::clone(&reported);
+    let parser = ChunkedParser::new()
+        .chunk_size(1)
+        .threads(2)
+        .on_progress(move |p| {
+            progress.lock().unwrap().push(p);
+            ControlFlow::Continue(())
+        });
+
+    let book = parser.parse_async(TEXT, &CancelToken::new()).await.unwrap();
+    assert_same(&book.book(), &Book::from_utf8(TEXT).unwrap());
+    assert_eq!(book.book().reconstruct(), TEXT);
+
+    let reported = reported.lock().unwrap();
+    assert!(reported.len() > 1);
+    for (i, p) in reported.iter().enumerate() {
+        assert_eq!(p.chunks_done, i + 1);
+        assert_eq!(p.chunks, reported.len());
+        assert_eq!(p.bytes, TEXT.len());
+    }
+    assert_eq!(reported.last().unwrap().bytes_done, TEXT.len());
+}
+
+#[tokio::test]
+async fn cancel() {
+    setup();
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let result = Book::parse_async(TEXT, &cancel).await;
+    assert_eq!(result.unwrap_err(), Error::Cancelled);
+
+    // The future returns before the slow parse is finished, the parse itself would
+    // succeed
+    let cancel = CancelToken::new();
+    let token = cancel.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        token.cancel();
+    });
+    let result = Book::parse_async("#!slow\nOne.", &cancel).await;
+    assert_eq!(result.unwrap_err(), Error::Cancelled);
+
+    // Chunks that are not started are skipped
+    let parser = ChunkedParser::new()
+        .chunk_size(1)
+        .threads(1)
+        .on_progress(|p: Progress| match p.chunks_done {
+            2 => ControlFlow::Break(()),
+            _ => ControlFlow::Continue(()),
+        });
+    assert_eq!(parser.parse(TEXT).unwrap_err(), Error::Cancelled);
+}
+
+#[tokio::test]
+async fn sentence_stream() {
+    setup();
+    let book = Book::parse_async(TEXT, &CancelToken::new()).await.unwrap();
+    let stream = book.sentence_stream();
+    assert_eq!(stream.size_hint(), (7, Some(7)));
+
+    let streamed = stream.collect::<Vec<_>>().await;
+    let expected = book
+        .sentences()
+        .map(|s| (s.info(), s.text().unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(streamed, expected);
+}
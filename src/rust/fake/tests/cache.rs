@@ -0,0 +1,91 @@
+//! Covers the on-disk cache over the test double of the native library.
+
+mod common;
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process, thread,
+};
+
+use bpw::{loaded_from, Book, Cache, Candidate};
+use common::{assert_same, setup};
+
+const TEXT: &str = "  One.   Two!\n\nThree? Four\nFive.";
+
+/// Returns an empty directory unique for the test
+fn cache_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("book-parse-cache-{}-{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn entries(cache: &Path) -> Vec<PathBuf> {
+    fs::read_dir(cache)
+        .map(|dir| dir.map(|e| e.unwrap().path()).collect())
+        .unwrap_or_default()
+}
+
+#[test]
+fn hit() {
+    setup();
+    let dir = cache_dir("hit");
+    let cache = Cache::new(&dir);
+
+    assert!(cache.load(TEXT).is_none());
+    let parsed = cache.book(TEXT).unwrap();
+    assert_eq!(entries(&dir).len(), 1);
+
+    // The entry is bound to the library that parsed the book
+    let library = match loaded_from() {
+        Some(Candidate::Explicit(path)) => path.display().to_string(),
+        other => panic!("the test double is loaded by `init`: {:?}", other),
+    };
+    let entry = fs::read(entries(&dir).pop().unwrap()).unwrap();
+    assert!(String::from_utf8_lossy(&entry).contains(&library));
+
+    let cached = cache.load(TEXT).unwrap();
+    assert_same(&cached, &parsed);
+    assert_same(&cache.book(TEXT).unwrap(), &Book::from_utf8(TEXT).unwrap());
+
+    assert!(cache.load("One. Two!").is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn broken_entry() {
+    setup();
+    let dir = cache_dir("broken");
+    let cache = Cache::new(&dir);
+
+    cache.book(TEXT).unwrap();
+    let entry = entries(&dir).pop().unwrap();
+    let bytes = fs::read(&entry).unwrap();
+
+    fs::write(&entry, &bytes[..bytes.len() - 1]).unwrap();
+    assert!(cache.load(TEXT).is_none());
+
+    // The entry is replaced on the next miss
+    cache.book(TEXT).unwrap();
+    assert_eq!(fs::read(&entry).unwrap(), bytes);
+    assert!(cache.load(TEXT).is_some());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn concurrent_store() {
+    setup();
+    let dir = cache_dir("concurrent");
+    let cache = Cache::new(&dir);
+
+    // A book is not `Send`, every thread stores a book of its own
+    thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| cache.store(&Book::from_utf8(TEXT).unwrap()).unwrap());
+        }
+    });
+
+    assert_eq!(entries(&dir).len(), 1);
+    assert_same(&cache.load(TEXT).unwrap(), &Book::from_utf8(TEXT).unwrap());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,86 @@
+//! Covers invalidation of cached books in a process that has not loaded the native
+//! library yet. Entries are stored by a child process, this binary has a single test
+//! so nothing loads the library before it.
+
+mod common;
+
+use std::{
+    env,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    process::{self, Command},
+    time::Duration,
+};
+
+use bpw::{loaded_from, Cache};
+
+const TEXT: &str = "One. Two!\n\nThree? Four\nFive.";
+
+/// Set for the child process, it contains the cache directory
+const CHILD_ENV: &str = "BOOK_PARSE_TEST_CACHE_DIR";
+
+/// Offset of the wrapper version in an entry: magic, format and the version length
+const VERSION_OFFSET: usize = 12;
+
+fn copy_library(dir: &Path, name: &str) -> PathBuf {
+    let path = dir.join(name);
+    fs::copy(common::library("book_parse_fake"), &path).unwrap();
+    path
+}
+
+#[test]
+fn invalidation() {
+    if let Some(dir) = env::var_os(CHILD_ENV) {
+        let dir = PathBuf::from(dir);
+        let cache = Cache::new(dir.join("cache")).library(dir.join("library"));
+        cache.book(TEXT).unwrap();
+        return;
+    }
+
+    let dir = env::temp_dir().join(format!("book-parse-fingerprint-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let library = copy_library(&dir, "library");
+    let moved = copy_library(&dir, "moved");
+
+    let status = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "invalidation"])
+        .env(CHILD_ENV, &dir)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // A hit does not load the native library
+    let cache = Cache::new(dir.join("cache")).library(&library);
+    assert!(cache.load(TEXT).is_some());
+    assert!(loaded_from().is_none());
+
+    // The same library at another path
+    let moved = Cache::new(dir.join("cache")).library(&moved);
+    assert!(moved.load(TEXT).is_none());
+
+    // Another version of the wrapper
+    let entry = fs::read_dir(dir.join("cache"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    let bytes = fs::read(&entry).unwrap();
+    let mut patched = bytes.clone();
+    patched[VERSION_OFFSET] ^= 0x01;
+    fs::write(&entry, &patched).unwrap();
+    assert!(cache.load(TEXT).is_none());
+    fs::write(&entry, &bytes).unwrap();
+    assert!(cache.load(TEXT).is_some());
+
+    // The library file is touched
+    let modified = fs::metadata(&library).unwrap().modified().unwrap();
+    let file = File::options().write(true).open(&library).unwrap();
+    file.set_modified(modified + Duration::from_secs(60))
+        .unwrap();
+    assert!(cache.load(TEXT).is_none());
+
+    assert!(loaded_from().is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
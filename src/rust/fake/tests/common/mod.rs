@@ -0,0 +1,58 @@
+//! Helpers shared by the tests.
+
+use std::{env, path::PathBuf, sync::Once};
+
+use bpw::Book;
+
+/// Text of several paragraphes with a blank line of spaces between them
+#[allow(dead_code)]
+pub const TEXT: &str = "One. Two!\n\n  \nThree? Four\nFive.\n\nSix. Seven.";
+
+/// Returns the path of the `cdylib` named `name` built for the tests.
+///
+/// Libraries of the package are placed next to the test binary or in its parent
+/// directory, examples are placed in the `examples` directory.
+pub fn library(name: &str) -> PathBuf {
+    let exe = env::current_exe().unwrap();
+    let deps = exe.parent().unwrap();
+    let name = format!(
+        "{}{}{}",
+        env::consts::DLL_PREFIX,
+        name,
+        env::consts::DLL_SUFFIX
+    );
+    let target = deps.parent().unwrap();
+
+    [deps, target, &target.join("examples")]
+        .iter()
+        .map(|dir| dir.join(&name))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| panic!("`{}` is not built, run `cargo test`", name))
+}
+
+/// Loads the test double, it is built as `cdylib` next to this test binary
+#[allow(dead_code)]
+pub fn setup() {
+    static INIT: Once = Once::new();
+
+    INIT.call_once(|| bpw::init(library("book_parse_fake")).unwrap());
+}
+
+/// Asserts that `book` has the same infos, texts and spans of paragraphes and sentences
+/// as `expected`
+#[allow(dead_code)]
+pub fn assert_same(book: &Book, expected: &Book) {
+    assert_eq!(book.info(), expected.info());
+
+    for (b, e) in book.paragraphes().zip(expected.paragraphes()) {
+        assert_eq!(b.info(), e.info());
+        assert_eq!(b.text(), e.text());
+        assert_eq!(b.span(), e.span());
+    }
+
+    for (b, e) in book.sentences().zip(expected.sentences()) {
+        assert_eq!(b.info(), e.info());
+        assert_eq!(b.text(), e.text());
+        assert_eq!(b.span(), e.span());
+    }
+}
@@ -0,0 +1,99 @@
+//! Covers loading of the native library. The library is loaded once per process, so
+//! these tests have their own binary.
+
+mod common;
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+use bpw::{
+    candidates, init, library_filename, loaded_from, Book, Candidate, Error, Wrapper, LIBRARY_ENV,
+};
+use common::{library, setup};
+
+#[test]
+fn init_errors() {
+    // Only the given path is tried
+    let missing = env::temp_dir()
+        .join("book-parse-missing")
+        .join(library_filename());
+    match init(&missing).unwrap_err() {
+        Error::LibraryNotFound { tried } => {
+            assert_eq!(tried.len(), 1);
+            assert_eq!(tried[0].0, Candidate::Explicit(missing.clone()));
+            assert!(!tried[0].1.is_empty());
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    match init(library("book_parse_partial")).unwrap_err() {
+        Error::MissingSymbol { name, reason } => {
+            assert_eq!(name, "book_info");
+            assert!(!reason.is_empty());
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    // Failed attempts do not load anything
+    assert!(loaded_from().is_none());
+    setup();
+    assert_eq!(
+        loaded_from(),
+        Some(Candidate::Explicit(library("book_parse_fake")))
+    );
+    assert_eq!(init(&missing).unwrap_err(), Error::AlreadyInitialized);
+    assert_eq!(Book::from_utf8("One.").unwrap().sentences_len(), 1);
+}
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+#[ignore = "allocates and reads 4 GiB, run with `cargo test -- --ignored`"]
+fn text_too_large() {
+    // The text is checked to be UTF-8, so all of its pages are read
+    let len = u32::MAX as usize + 1;
+    let text = String::from_utf8(vec![0; len]).unwrap();
+    assert_eq!(
+        Wrapper::from_utf8(&text).unwrap_err(),
+        Error::TextTooLarge { len }
+    );
+}
+
+#[test]
+fn discovery_order() {
+    let explicit = Path::new("explicit").join(library_filename());
+
+    env::set_var(LIBRARY_ENV, "");
+    let without_env = candidates(None);
+    env::set_var(LIBRARY_ENV, "from-env");
+    let list = candidates(Some(&explicit));
+    env::remove_var(LIBRARY_ENV);
+
+    assert_eq!(list[0], Candidate::Explicit(explicit));
+    assert_eq!(list[1], Candidate::Environment(PathBuf::from("from-env")));
+    assert_eq!(list[2..], without_env[..]);
+
+    // `lib/<target-triple>/` in the directory of the executable and a few parents, never
+    // in the current directory
+    let exe = env::current_exe().unwrap();
+    let (bundled, system) = without_env.split_at(without_env.len() - 1);
+    let paths = bundled
+        .iter()
+        .map(|c| match c {
+            Candidate::Bundled(path) => path,
+            c => panic!("unexpected candidate: {:?}", c),
+        })
+        .collect::<Vec<_>>();
+    let first = paths[0].strip_prefix(exe.parent().unwrap()).unwrap();
+    assert_eq!(first.iter().next().unwrap(), "lib");
+    assert!(paths.iter().all(|path| path.ends_with(library_filename())));
+    assert_eq!(paths.len(), 6);
+    assert!(paths
+        .iter()
+        .all(|path| path.starts_with(exe.ancestors().nth(6).unwrap())));
+    assert_eq!(
+        system,
+        [Candidate::System(library_filename().into())].as_slice()
+    );
+}
@@ -0,0 +1,743 @@
+//! Covers the wrapper over the test double of the native library.
+
+mod common;
+
+use std::{
+    cell::Cell,
+    env, fs,
+    io::{self, Read},
+    process,
+};
+
+use bpw::{
+    Book, BookInfo, ChunkedParser, Error, MemorySegmenter, ParagraphId, ParagraphInfo, Report,
+    Segmenter, SentenceId, SentenceInfo, StreamingBook, StringSize, Violation, Wrapper,
+};
+use common::{setup, TEXT};
+
+#[test]
+fn parses_text() {
+    setup();
+    let book = Book::from_utf8("One. Two!\n\nThree? Four\nFive.").unwrap();
+
+    let info = book.info();
+    assert_eq!(info.paragraphes, 3);
+    assert_eq!(info.sentences, 5);
+    assert_eq!(info.size.bytes, 27);
+
+    let paragraphes = book
+        .paragraphes()
+        .map(|p| p.text().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(paragraphes, ["One. Two!", "Three? Four", "Five."]);
+
+    let sentences = book
+        .sentences()
+        .map(|s| s.text().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(sentences, ["One.", "Two!", "Three?", "Four", "Five."]);
+}
+
+#[test]
+fn null_book() {
+    setup();
+    assert_eq!(
+        Book::from_utf8("#!null\nText.").unwrap_err(),
+        Error::NullBook
+    );
+}
+
+#[test]
+fn empty_input() {
+    setup();
+    assert_eq!(Book::from_utf8("").unwrap_err(), Error::EmptyInput);
+    assert_eq!(
+        Book::from_utf8("#!empty\nText.").unwrap_err(),
+        Error::EmptyInput
+    );
+}
+
+#[test]
+fn invalid_utf8() {
+    setup();
+    let book = Book::from_utf8("#!invalid-utf8\nText.").unwrap();
+    let sentence = book.sentences().next().unwrap();
+    assert!(matches!(sentence.text(), Err(Error::InvalidUtf8(_))));
+}
+
+#[test]
+fn out_of_range() {
+    setup();
+    let wrapper = Wrapper::from_utf8("One. Two.").unwrap();
+    assert_eq!(
+        wrapper.sentence_text(SentenceId::from(2)),
+        Err(Error::SentenceOutOfRange {
+            index: SentenceId::from(2),
+            len: 2
+        })
+    );
+    assert_eq!(
+        wrapper.sentence_info(SentenceId::from(2)),
+        Default::default()
+    );
+    assert_eq!(
+        wrapper.paragraph_info(ParagraphId::from(1)),
+        Default::default()
+    );
+}
+
+#[test]
+fn already_initialized() {
+    setup();
+    assert_eq!(bpw::init("any").unwrap_err(), Error::AlreadyInitialized);
+}
+
+#[test]
+fn random_access() {
+    setup();
+    let book = Book::from_utf8("One. Two.\nThree.").unwrap();
+    assert_eq!(book.paragraphes_len(), 2);
+    assert_eq!(book.sentences_len(), 3);
+
+    let sentence = book.sentence(SentenceId::from(2)).unwrap();
+    assert_eq!(sentence.text().unwrap(), "Three.");
+    assert!(book.sentence(SentenceId::from(3)).is_none());
+
+    let paragraph = book.paragraph(ParagraphId::from(1)).unwrap();
+    assert_eq!(paragraph.text().unwrap(), "Three.");
+    assert!(book.paragraph(ParagraphId::from(2)).is_none());
+}
+
+#[test]
+fn spans() {
+    setup();
+    let text = "  One.   Two!\n\nThree.";
+    let book = Book::from_utf8(text).unwrap();
+
+    let spans = book.sentences().map(|s| s.span()).collect::<Vec<_>>();
+    assert_eq!(spans, [Some(2..6), Some(9..13), Some(15..21)]);
+
+    let sentence = book.sentence(SentenceId::from(1)).unwrap();
+    assert_eq!(sentence.text_ref(), Some("Two!"));
+
+    let paragraph = book.paragraph(ParagraphId::from(0)).unwrap();
+    assert_eq!(paragraph.span(), Some(2..13));
+    assert_eq!(paragraph.text_ref(), Some("One.   Two!"));
+}
+
+#[test]
+fn materialize() {
+    setup();
+    let text = String::from("One. Two!\nThree.");
+    let owned = Book::from_utf8(&text).unwrap().materialize().unwrap();
+    drop(text);
+
+    let handle = std::thread::spawn(move || {
+        let sentences = owned
+            .sentences()
+            .map(|s| s.text().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(sentences, ["One.", "Two!", "Three."]);
+
+        let paragraph = owned.paragraph(ParagraphId::from(1)).unwrap();
+        assert_eq!(paragraph.text_ref(), Some("Three."));
+        owned
+    });
+
+    let owned = handle.join().unwrap();
+    assert_eq!(owned.info().paragraphes, 2);
+    assert_eq!(owned.text(), "One. Two!\nThree.");
+}
+
+#[test]
+fn navigation() {
+    setup();
+    let book = Book::from_utf8("One. Two.\nThree.\nFour. Five. Six.").unwrap();
+    let text = |s: Option<bpw::Sentence>| s.map(|s| s.text().unwrap());
+
+    let two = book.sentence(SentenceId::from(1)).unwrap();
+    assert_eq!(text(two.prev()), Some("One.".into()));
+    assert_eq!(text(two.next()), Some("Three.".into()));
+    assert!(!two.is_first() && two.is_last());
+    assert_eq!(two.paragraph().unwrap().text().unwrap(), "One. Two.");
+
+    let first = book.sentence(SentenceId::from(0)).unwrap();
+    assert!(first.prev().is_none());
+    assert!(first.is_first() && !first.is_last());
+    assert!(book.sentence(SentenceId::from(5)).unwrap().next().is_none());
+
+    let context = |index: u32, n| {
+        book.sentence(index.into())
+            .unwrap()
+            .context(n)
+            .map(|s| s.text().unwrap())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(context(2, 1), ["Two.", "Three.", "Four."]);
+    assert_eq!(context(0, 2), ["One.", "Two.", "Three."]);
+    assert_eq!(context(5, 0), ["Six."]);
+    assert_eq!(context(3, usize::MAX).len(), 6);
+
+    let last = book.paragraph(ParagraphId::from(2)).unwrap();
+    assert_eq!(text(last.first_sentence()), Some("Four.".into()));
+    assert_eq!(text(last.last_sentence()), Some("Six.".into()));
+    assert!(last.next().is_none());
+    assert_eq!(last.prev().unwrap().text().unwrap(), "Three.");
+    assert!(last.prev().unwrap().prev().unwrap().prev().is_none());
+}
+
+#[test]
+fn iterators() {
+    setup();
+    let book = Book::from_utf8("One. Two.\nThree.\nFour. Five. Six.").unwrap();
+    fn texts<'a>(sentences: impl Iterator<Item = bpw::Sentence<'a>>) -> Vec<String> {
+        sentences.map(|s| s.text().unwrap()).collect()
+    }
+
+    let mut sentences = book.sentences();
+    assert_eq!(sentences.len(), 6);
+    assert_eq!(sentences.next_back().unwrap().text().unwrap(), "Six.");
+    assert_eq!(sentences.nth(2).unwrap().text().unwrap(), "Three.");
+    assert_eq!(sentences.len(), 2);
+    assert_eq!(texts(sentences), ["Four.", "Five."]);
+
+    let rev = book.sentences().rev().map(|s| s.info().index.into());
+    assert_eq!(rev.collect::<Vec<u32>>(), [5, 4, 3, 2, 1, 0]);
+
+    let last = book.paragraph(ParagraphId::from(2)).unwrap();
+    assert_eq!(last.sentences().len(), 3);
+    assert_eq!(texts(last.sentences().rev()), ["Six.", "Five.", "Four."]);
+
+    assert_eq!(
+        texts(book.sentences_in(SentenceId::from(1)..SentenceId::from(3))),
+        ["Two.", "Three."]
+    );
+    assert_eq!(
+        book.sentences_in(SentenceId::from(4)..SentenceId::from(10))
+            .len(),
+        2
+    );
+    assert_eq!(
+        book.sentences_in(SentenceId::from(10)..SentenceId::from(2))
+            .len(),
+        0
+    );
+
+    let paragraphes = book.paragraphes_in(ParagraphId::from(1)..ParagraphId::from(3));
+    assert_eq!(paragraphes.len(), 2);
+    let paragraphes = paragraphes.map(|p| p.text().unwrap()).collect::<Vec<_>>();
+    assert_eq!(paragraphes, ["Three.", "Four. Five. Six."]);
+}
+
+#[test]
+fn offsets() {
+    setup();
+    // Spans: "One." 0..4, "Два!" 6..13 (chars 6..10), "Three." 15..21 (chars 12..18)
+    let book = Book::from_utf8("One.  Два!\n\nThree.").unwrap();
+    let index = |s: Option<bpw::Sentence>| s.map(|s| u32::from(s.info().index));
+    let p_index = |p: Option<bpw::Paragraph>| p.map(|p| u32::from(p.info().index));
+
+    assert_eq!(index(book.sentence_at_byte(0)), Some(0));
+    assert_eq!(index(book.sentence_at_byte(3)), Some(0));
+    assert_eq!(index(book.sentence_at_byte(4)), None);
+    assert_eq!(index(book.sentence_at_byte(6)), Some(1));
+    assert_eq!(index(book.sentence_at_byte(12)), Some(1));
+    assert_eq!(index(book.sentence_at_byte(13)), None);
+    assert_eq!(index(book.sentence_at_byte(15)), Some(2));
+    assert_eq!(index(book.sentence_at_byte(20)), Some(2));
+    assert_eq!(index(book.sentence_at_byte(21)), None);
+
+    assert_eq!(index(book.sentence_at_char(9)), Some(1));
+    assert_eq!(index(book.sentence_at_char(10)), None);
+    assert_eq!(index(book.sentence_at_char(12)), Some(2));
+    assert_eq!(index(book.sentence_at_char(18)), None);
+
+    // A gap between sentences belongs to their paragraph, a gap between paragraphes
+    // does not
+    assert_eq!(p_index(book.paragraph_at_byte(4)), Some(0));
+    assert_eq!(p_index(book.paragraph_at_byte(12)), Some(0));
+    assert_eq!(p_index(book.paragraph_at_byte(13)), None);
+    assert_eq!(p_index(book.paragraph_at_byte(15)), Some(1));
+    assert_eq!(p_index(book.paragraph_at_char(9)), Some(0));
+    assert_eq!(p_index(book.paragraph_at_char(11)), None);
+    assert_eq!(p_index(book.paragraph_at_char(12)), Some(1));
+    assert_eq!(p_index(book.paragraph_at_char(usize::MAX)), None);
+
+    // Sentences that are not found in the original text are never returned
+    let book = Book::from_utf8("#!invalid-utf8\nOne. Two.").unwrap();
+    assert_eq!(index(book.sentence_at_byte(15)), None);
+    assert_eq!(p_index(book.paragraph_at_byte(15)), None);
+}
+
+#[test]
+fn index() {
+    setup();
+    let book = Book::from_utf8("One. Два!\nThree.\nFour. Five.").unwrap();
+    let index = book.index();
+
+    assert_eq!(index.sentences().len(), 5);
+    assert_eq!(index.paragraphes().len(), 3);
+    assert_eq!(
+        index.sentence_info(SentenceId::from(1)),
+        Some(book.sentence(SentenceId::from(1)).unwrap().info())
+    );
+    assert_eq!(index.sentence_info(SentenceId::from(5)), None);
+
+    let size = index.sentences_size(SentenceId::from(1)..SentenceId::from(3));
+    assert_eq!((size.bytes, size.symbols), (13, 10));
+    let size = index.sentences_size(SentenceId::from(0)..SentenceId::from(100));
+    assert_eq!((size.bytes, size.symbols), (27, 24));
+    let size = index.sentences_size(SentenceId::from(3)..SentenceId::from(1));
+    assert_eq!((size.bytes, size.symbols), (0, 0));
+
+    let size = index.paragraphes_size(ParagraphId::from(1)..ParagraphId::from(3));
+    assert_eq!((size.bytes, size.symbols), (17, 17));
+}
+
+/// Returns a segmenter of one paragraph with two sentences, sizes of the sentences add
+/// up to more than `u32::MAX`
+fn oversized() -> MemorySegmenter {
+    let size = StringSize {
+        bytes: u32::MAX,
+        symbols: u32::MAX,
+    };
+    let paragraph = ParagraphInfo {
+        index: ParagraphId::from(0),
+        sentence_first: SentenceId::from(0),
+        sentences: 2,
+        size,
+    };
+    let sentence = |i: u32| SentenceInfo {
+        index: SentenceId::from(i),
+        s_number: i,
+        p_index: ParagraphId::from(0),
+        size,
+    };
+    let info = BookInfo {
+        paragraphes: 1,
+        sentences: 2,
+        size,
+    };
+
+    MemorySegmenter::new(
+        info,
+        vec![(paragraph, "One. Two.".to_owned())],
+        vec![
+            (sentence(0), "One.".to_owned()),
+            (sentence(1), "Two.".to_owned()),
+        ],
+    )
+}
+
+#[test]
+fn index_overflow() {
+    let book = Book::with_segmenter(oversized(), "One. Two.").unwrap();
+    let index = book.index();
+
+    let size = index.sentences_size(SentenceId::from(0)..SentenceId::from(2));
+    assert_eq!((size.bytes, size.symbols), (u32::MAX, u32::MAX));
+    let size = index.sentences_size(SentenceId::from(1)..SentenceId::from(2));
+    assert_eq!((size.bytes, size.symbols), (u32::MAX, u32::MAX));
+}
+
+/// Segmenter that allows a limited number of calls for infos of sentences, it is not
+/// `Sync`
+#[derive(Debug)]
+struct Limited {
+    inner: Wrapper,
+    sentence_infos: Cell<usize>,
+}
+
+impl Segmenter for Limited {
+    fn book_info(&self) -> BookInfo {
+        self.inner.book_info()
+    }
+
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        self.inner.paragraph_info(index)
+    }
+
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error> {
+        self.inner.paragraph_text(index)
+    }
+
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        let left = self.sentence_infos.get();
+        assert!(left > 0, "too many calls for infos of sentences");
+        self.sentence_infos.set(left - 1);
+        self.inner.sentence_info(index)
+    }
+
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error> {
+        self.inner.sentence_text(index)
+    }
+}
+
+#[test]
+fn index_caches_infos() {
+    setup();
+    let text = "One. Two!\nThree.";
+    // Infos are read once without the index and once to build it
+    let segmenter = Limited {
+        inner: Wrapper::from_utf8(text).unwrap(),
+        sentence_infos: Cell::new(6),
+    };
+    let book = Book::with_segmenter(segmenter, text).unwrap();
+    let infos = || book.sentences().map(|s| s.info()).collect::<Vec<_>>();
+
+    let expected = infos();
+    book.index();
+    assert_eq!(infos(), expected);
+}
+
+#[test]
+fn write_text_into() {
+    setup();
+    let book = Book::from_utf8("One. Два!\nThree.").unwrap();
+
+    let mut buf = String::from(">");
+    book.sentence(SentenceId::from(1))
+        .unwrap()
+        .write_text_into(&mut buf)
+        .unwrap();
+    book.paragraph(ParagraphId::from(0))
+        .unwrap()
+        .write_text_into(&mut buf)
+        .unwrap();
+    assert_eq!(buf, ">Два!One. Два!");
+
+    let mut texts = vec![];
+    book.for_each_sentence_text(|id, text| texts.push(format!("{}:{}", id, text)))
+        .unwrap();
+    assert_eq!(texts, ["0:One.", "1:Два!", "2:Three."]);
+
+    let broken = Book::from_utf8("#!invalid-utf8\nOne. Two.").unwrap();
+    let mut buf = String::from("kept");
+    let sentence = broken.sentence(SentenceId::from(0)).unwrap();
+    assert!(matches!(
+        sentence.write_text_into(&mut buf),
+        Err(Error::InvalidUtf8(_))
+    ));
+    assert_eq!(buf, "kept");
+
+    let mut calls = 0;
+    let result = broken.for_each_sentence_text(|_, _| calls += 1);
+    assert!(matches!(result, Err(Error::InvalidUtf8(_))));
+    assert_eq!(calls, 0);
+}
+
+#[test]
+fn short_write() {
+    setup();
+    let book = Book::from_utf8("#!short-write\nOne. Два!").unwrap();
+
+    let mut buf = String::from("kept");
+    let sentence = book.sentence(SentenceId::from(1)).unwrap();
+    assert_eq!(
+        sentence.write_text_into(&mut buf),
+        Err(Error::ShortWrite {
+            expected: 7,
+            written: 6
+        })
+    );
+    assert_eq!(buf, "kept");
+}
+
+#[test]
+fn long_write() {
+    setup();
+    let book = Book::from_utf8("#!long-write\nOne. Два!").unwrap();
+    let paragraph = book.paragraph(ParagraphId::from(0)).unwrap();
+    assert_eq!(paragraph.text(), Err(Error::LongWrite { expected: 12 }));
+}
+
+#[test]
+fn wrong_symbols() {
+    setup();
+    let book = Book::from_utf8("#!wrong-symbols\nOne. Два!").unwrap();
+    let sentence = book.sentence(SentenceId::from(1)).unwrap();
+    assert_eq!(
+        sentence.text(),
+        Err(Error::SymbolsMismatch {
+            expected: 5,
+            actual: 4
+        })
+    );
+}
+
+#[test]
+fn paranoid() {
+    setup();
+    let text = "#!wrong-p-index\nOne. Two.\nThree.";
+
+    // The sentence reports the next paragraph, it is unnoticed without checks
+    let wrapper = Wrapper::from_utf8(text).unwrap();
+    assert_eq!(wrapper.sentence_text(SentenceId::from(0)).unwrap(), "One.");
+
+    let wrapper = Wrapper::from_utf8(text).unwrap().paranoid();
+    assert!(matches!(
+        wrapper.sentence_text(SentenceId::from(0)),
+        Err(Error::InconsistentInfo { .. })
+    ));
+    assert!(matches!(
+        wrapper.sentence_text(SentenceId::from(2)),
+        Err(Error::InconsistentInfo { .. })
+    ));
+    assert_eq!(
+        wrapper.paragraph_text(ParagraphId::from(0)).unwrap(),
+        "One. Two."
+    );
+
+    let wrapper = Wrapper::from_utf8("One. Two.\nThree.").unwrap().paranoid();
+    let sentences = (0..3)
+        .map(|i| wrapper.sentence_text(SentenceId::from(i)).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(sentences, ["One.", "Two.", "Three."]);
+}
+
+#[test]
+fn verify() {
+    setup();
+    let book = Book::from_utf8("One. Two.\n\nThree? Four\nFive.").unwrap();
+    assert_eq!(book.verify(), Report::default());
+
+    let book = Book::from_utf8("#!wrong-p-index\nOne. Two.\nThree.").unwrap();
+    let violations = book.verify().violations;
+    assert_eq!(
+        violations,
+        [
+            Violation::WrongParagraph {
+                index: SentenceId::from(0),
+                p_index: ParagraphId::from(1),
+                expected: Some(ParagraphId::from(0)),
+            },
+            Violation::WrongParagraph {
+                index: SentenceId::from(1),
+                p_index: ParagraphId::from(1),
+                expected: Some(ParagraphId::from(0)),
+            },
+            Violation::WrongParagraph {
+                index: SentenceId::from(2),
+                p_index: ParagraphId::from(2),
+                expected: Some(ParagraphId::from(1)),
+            },
+        ]
+    );
+
+    // Every size has an extra symbol, so sums of two items differ by one
+    let book = Book::from_utf8("#!wrong-symbols\nOne. Two.\nThree.").unwrap();
+    let report = book.verify();
+    assert_eq!(report.violations.len(), 2);
+    assert!(matches!(
+        report.violations[0],
+        Violation::ParagraphSize { index, size, expected }
+            if u32::from(index) == 0 && size.symbols + 1 == expected.symbols
+    ));
+    assert!(matches!(report.violations[1], Violation::BookSize { .. }));
+    assert!(report
+        .to_string()
+        .starts_with("2 violations:\n  paragraph 0 has size"));
+}
+
+#[test]
+fn verify_overflow() {
+    let book = Book::with_segmenter(oversized(), "One. Two.").unwrap();
+    let size = book.info().size;
+    assert_eq!(
+        book.verify().violations,
+        [Violation::ParagraphSizeOverflow {
+            index: ParagraphId::from(0),
+            size
+        }]
+    );
+}
+
+#[test]
+fn gaps() {
+    setup();
+    let text = "\t One.  Two!\r\n\n  Three.\n";
+    let book = Book::from_utf8(text).unwrap();
+    assert_eq!(book.reconstruct(), text);
+
+    // Sentences that cannot be read are not located, the original text is kept
+    let unread = "#!invalid-utf8\nOne.  Two!";
+    assert_eq!(Book::from_utf8(unread).unwrap().reconstruct(), unread);
+
+    let gaps = book
+        .sentences()
+        .map(|s| s.gap_before().unwrap().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(gaps, ["\t ", "  ", "\r\n\n  "]);
+
+    let last = book.sentence(SentenceId::from(2)).unwrap();
+    assert_eq!(last.gap_after(), Some("\n"));
+
+    let paragraph = book.paragraph(ParagraphId::from(1)).unwrap();
+    assert_eq!(paragraph.gap_before(), Some("\r\n\n  "));
+    assert_eq!(paragraph.prev().unwrap().gap_after(), Some("\r\n\n  "));
+}
+
+#[test]
+fn chunked() {
+    setup();
+    let book = Book::from_utf8(TEXT).unwrap();
+
+    for chunk_size in [1, 5, 12, 100] {
+        let chunked = ChunkedParser::new()
+            .chunk_size(chunk_size)
+            .threads(3)
+            .parse(TEXT)
+            .unwrap();
+
+        assert_eq!(chunked.paragraphes_len(), book.paragraphes_len() as u64);
+        assert_eq!(chunked.sentences_len(), book.sentences_len() as u64);
+
+        for (g, s) in chunked.sentences().zip(book.sentences()) {
+            assert_eq!(g.id(), u64::from(u32::from(s.info().index)));
+            assert_eq!(g.paragraph_id(), u64::from(u32::from(s.info().p_index)));
+            assert_eq!(g.local().text(), s.text());
+            let span = s.span().map(|r| r.start as u64..r.end as u64);
+            assert_eq!(g.span(), span);
+        }
+
+        for (g, p) in chunked.paragraphes().zip(book.paragraphes()) {
+            assert_eq!(g.id(), u64::from(u32::from(p.info().index)));
+            assert_eq!(
+                g.first_sentence(),
+                u64::from(u32::from(p.info().sentence_first))
+            );
+            assert_eq!(g.local().text(), p.text());
+        }
+
+        let six = chunked.sentence(5).unwrap();
+        assert_eq!(six.local().text().unwrap(), "Six.");
+        assert_eq!(six.paragraph_id(), 3);
+        assert!(chunked.sentence(7).is_none());
+        assert_eq!(
+            chunked.paragraph(2).unwrap().local().text().unwrap(),
+            "Five."
+        );
+        assert!(chunked.paragraph(4).is_none());
+    }
+
+    assert_eq!(
+        ChunkedParser::new()
+            .chunk_size(1)
+            .parse(" \n\n \n")
+            .unwrap_err(),
+        Error::EmptyInput
+    );
+}
+
+/// Reader that returns at most 3 bytes at once
+struct Trickle<'a>(&'a [u8]);
+
+impl Read for Trickle<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.0.len()).min(3);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+        Ok(len)
+    }
+}
+
+#[test]
+fn streaming() {
+    setup();
+    let book = Book::from_utf8(TEXT).unwrap();
+    let longest = TEXT.lines().map(|l| l.len() + 1).max().unwrap();
+
+    for chunk_size in [1, 5, 12, 100] {
+        let mut stream =
+            StreamingBook::from_reader(Trickle(TEXT.as_bytes())).chunk_size(chunk_size);
+        let mut chunks = vec![];
+        for chunk in &mut stream {
+            let chunk = chunk.unwrap();
+            assert!(chunk.book().text().len() < chunk_size + longest);
+            chunks.push(chunk);
+        }
+
+        assert_eq!(stream.bytes_read(), TEXT.len() as u64);
+        assert_eq!(stream.paragraphes_read(), book.paragraphes_len() as u64);
+        assert_eq!(stream.sentences_read(), book.sentences_len() as u64);
+
+        let sentences = chunks.iter().flat_map(|c| c.sentences());
+        for (g, s) in sentences.zip(book.sentences()) {
+            assert_eq!(g.id(), u64::from(u32::from(s.info().index)));
+            assert_eq!(g.paragraph_id(), u64::from(u32::from(s.info().p_index)));
+            assert_eq!(g.local().text(), s.text());
+            let span = s.span().map(|r| r.start as u64..r.end as u64);
+            assert_eq!(g.span(), span);
+        }
+
+        let paragraphes = chunks.iter().flat_map(|c| c.paragraphes());
+        for (g, p) in paragraphes.zip(book.paragraphes()) {
+            assert_eq!(g.id(), u64::from(u32::from(p.info().index)));
+            assert_eq!(g.local().text(), p.text());
+        }
+    }
+
+    assert_eq!(StreamingBook::from_reader(" \n\n".as_bytes()).count(), 0);
+
+    // The invalid chunk is skipped, but its bytes are counted
+    let mut stream = StreamingBook::from_reader(&b"One.\nTwo\xff.\nThree."[..]).chunk_size(1);
+    let mut next = || {
+        let chunk = stream.next().unwrap();
+        chunk.map(|c| (c.offset(), c.book().text().to_owned()))
+    };
+    assert_eq!(next(), Ok((0, "One.\n".to_owned())));
+    assert!(matches!(
+        next(),
+        Err(Error::Io {
+            kind: io::ErrorKind::InvalidData,
+            ..
+        })
+    ));
+    assert_eq!(next(), Ok((11, "Three.".to_owned())));
+    assert!(stream.next().is_none());
+    assert_eq!(stream.bytes_read(), 17);
+}
+
+#[test]
+fn from_path() {
+    setup();
+    let text = "One. Two!\n\nThree? Four\nFive.";
+    let path = env::temp_dir().join(format!("book-parse-mapped-{}.txt", process::id()));
+
+    fs::write(&path, text).unwrap();
+    let book = Book::from_path(&path).unwrap();
+    let expected = Book::from_utf8(text).unwrap();
+    assert_eq!(book.info(), expected.info());
+    assert_eq!(book.text(), text);
+    for (s, e) in book.sentences().zip(expected.sentences()) {
+        assert_eq!(s.info(), e.info());
+        assert_eq!(s.text(), e.text());
+        assert_eq!(s.span(), e.span());
+        assert_eq!(s.text_ref(), e.text_ref());
+    }
+
+    assert_eq!(book.sentences_len(), 5);
+
+    for empty in ["", " \n\n"] {
+        fs::write(&path, empty).unwrap();
+        assert_eq!(Book::from_path(&path).unwrap_err(), Error::EmptyInput);
+    }
+
+    fs::write(&path, b"One.\nTwo\xff.").unwrap();
+    assert!(matches!(
+        Book::from_path(&path).unwrap_err(),
+        Error::Io {
+            kind: io::ErrorKind::InvalidData,
+            ..
+        }
+    ));
+
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        Book::from_path(&path).unwrap_err(),
+        Error::Io {
+            kind: io::ErrorKind::NotFound,
+            ..
+        }
+    ));
+}
@@ -3,12 +3,14 @@ use std::{
     io::{self, Write},
     os::raw::c_uint,
     path::Path,
+    process,
     time::Duration,
 };
 
 use clap::{crate_authors, crate_version, Clap};
 use tokio::{
     io::AsyncWriteExt,
+    signal,
     sync::oneshot::{channel, error::TryRecvError, Sender},
     task::JoinHandle,
     time::sleep,
@@ -76,25 +78,61 @@ struct Opts {
     )]
     view_index_sentence: bool,
 
+    #[clap(
+        long,
+        about = "Keeps the original formatting inside parts.",
+        long_about = "Keeps the original formatting inside parts: sentences are written as they are in the source file with whitespaces, line breaks and blank lines between them instead of normalized sentences joined by a space or a line break. Parts with sentences that are not found in the source file are joined as without this option."
+    )]
+    preserve_formatting: bool,
+
     #[clap(long, about = "Show verbose info when splitting stady is active")]
     verbose_splitting: bool,
+
+    #[clap(
+        long,
+        about = "Path to the native `book_parse` library.",
+        long_about = "Path to the native `book_parse` library. If this option is empty, the library is searched in `BOOK_PARSE_LIB` environment variable, `lib/<target-triple>/` directory and the system search path."
+    )]
+    library: Option<String>,
+
+    #[clap(
+        long,
+        about = "Checks consistency of the parsed book and stops if it is broken."
+    )]
+    verify: bool,
+
+    #[cfg(feature = "rust-segmenter")]
+    #[clap(
+        long,
+        about = "Parses the text with the pure Rust segmentation instead of the native library."
+    )]
+    rust_segmenter: bool,
+
+    #[cfg(feature = "cache")]
+    #[clap(
+        long,
+        about = "Directory to cache parsed books.",
+        long_about = "Directory to cache parsed books. A book is taken from the cache if its text was parsed before by the same native library, the library is not loaded then."
+    )]
+    cache: Option<String>,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error + 'static>> {
     let opts: Opts = Opts::parse();
-    let text = ::std::fs::read_to_string(&opts.source)?;
 
-    println!(
-        "Read file `{}`, size: {} symbols, {} bytes",
-        opts.source,
-        text.chars().count(),
-        text.as_bytes().len()
-    );
+    #[cfg(feature = "cache")]
+    let init_library = opts.cache.is_none();
+    #[cfg(not(feature = "cache"))]
+    let init_library = true;
+
+    if let (Some(library), true) = (&opts.library, init_library) {
+        bpw::init(library)?;
+    }
 
     let writer = get_writer(opts.output.as_ref())?;
 
-    parse_book(text, writer, &opts).await?;
+    parse_book(writer, &opts).await?;
 
     Ok(())
 }
@@ -107,10 +145,10 @@ fn process(action: &str) -> (JoinHandle<()>, Sender<&'static str>) {
         let mut stdout = ::tokio::io::stdout();
         let status = [br"|", br"/", br"-", br"\"];
         let mut i = 0;
-        stdout.write(action.as_bytes()).await.unwrap();
+        stdout.write_all(action.as_bytes()).await.unwrap();
 
         loop {
-            stdout.write(status[i]).await.unwrap();
+            stdout.write_all(status[i]).await.unwrap();
             stdout.flush().await.unwrap();
             i = match i {
                 i if i == status.len() - 1 => 0,
@@ -120,13 +158,13 @@ fn process(action: &str) -> (JoinHandle<()>, Sender<&'static str>) {
             match rx.try_recv() {
                 Ok(msg) => {
                     stdout
-                        .write(format!("\u{8} {}\r\n", msg).as_bytes())
+                        .write_all(format!("\u{8} {}\r\n", msg).as_bytes())
                         .await
                         .unwrap();
                     break;
                 }
                 Err(TryRecvError::Closed) => {
-                    stdout.write("\u{8}\r\n".as_bytes()).await.unwrap();
+                    stdout.write_all("\u{8}\r\n".as_bytes()).await.unwrap();
                     break;
                 }
                 _ => (),
@@ -134,46 +172,115 @@ fn process(action: &str) -> (JoinHandle<()>, Sender<&'static str>) {
 
             stdout.flush().await.unwrap();
             sleep(Duration::from_millis(300)).await;
-            stdout.write("\u{8}".as_bytes()).await.unwrap();
+            stdout.write_all("\u{8}".as_bytes()).await.unwrap();
         }
     });
 
     (handle, tx)
 }
 
+/// Cancels parsing on Ctrl-C, the process exits on Ctrl-C if nothing is parsed
+fn cancel_on_ctrl_c(cancel: &CancelToken) {
+    let cancel = cancel.clone();
+
+    ::tokio::spawn(async move {
+        while signal::ctrl_c().await.is_ok() {
+            if cancel.is_cancelled() {
+                process::exit(130);
+            }
+            cancel.cancel();
+        }
+    });
+}
+
+/// Parses the source file, it is memory-mapped for the native library or read into
+/// `text` for the cache and the Rust segmentation
+#[cfg_attr(
+    not(any(feature = "cache", feature = "rust-segmenter")),
+    allow(unused_variables)
+)]
+async fn from_path<'a>(
+    opts: &Opts,
+    text: &'a mut Option<String>,
+) -> Result<Book<'a>, Box<dyn std::error::Error + 'static>> {
+    #[cfg(feature = "rust-segmenter")]
+    if opts.rust_segmenter {
+        let text = text.insert(::std::fs::read_to_string(&opts.source)?);
+        return Ok(Book::from_utf8_rust(text)?);
+    }
+
+    #[cfg(feature = "cache")]
+    if let Some(ref dir) = opts.cache {
+        let cache = match opts.library {
+            Some(ref library) => Cache::new(dir).library(library),
+            None => Cache::new(dir),
+        };
+        let text = text.insert(::std::fs::read_to_string(&opts.source)?);
+        return Ok(cache.book(text)?);
+    }
+
+    let cancel = CancelToken::new();
+    cancel_on_ctrl_c(&cancel);
+    let book = Book::from_path_async(&opts.source, &cancel).await;
+    // Parsing is over, so the next Ctrl-C exits
+    cancel.cancel();
+
+    Ok(book?)
+}
+
 async fn parse_book(
-    text: String,
     mut writer: impl Write,
     opts: &Opts,
 ) -> Result<(), Box<dyn std::error::Error + 'static>> {
     let (handle, tx) = process("Parsing a book... ");
-    let book = Book::from_utf8(&text)?;
+    let mut text = None;
+    let book = from_path(opts, &mut text).await?;
     tx.send("ok").unwrap();
     handle.await.unwrap();
 
+    println!(
+        "Read file `{}`, size: {} symbols, {} bytes",
+        opts.source,
+        book.text().chars().count(),
+        book.text().len()
+    );
+
     println!(
         "Found: {} sentences, {} paragraphes",
         book.info().sentences,
         book.info().paragraphes
     );
 
+    if opts.verify {
+        let report = book.verify();
+        println!("Verification: {}", report);
+        if !report.is_ok() {
+            return Err("the parsed book is inconsistent".into());
+        }
+    }
+
     let (handle, tx) = process("Splitting parts... ");
 
     let mut parts: Vec<Vec<Sentence>> = vec![];
     let mut current_part = vec![];
 
-    let symbols = |sentences: &Vec<Sentence>| {
-        sentences
-            .iter()
-            .map(|s: &Sentence| s.info().size.symbols)
-            .sum::<c_uint>()
+    // Infos and sizes are taken from the index instead of the native library
+    let index = book.index();
+
+    // Parts are consecutive sentences, so their size is a difference of prefix sums
+    let symbols = |sentences: &Vec<Sentence>| match (sentences.first(), sentences.last()) {
+        (Some(first), Some(last)) => {
+            let end = c_uint::from(last.info().index) + 1;
+            index.sentences_size(first.info().index..end.into()).symbols
+        }
+        _ => 0,
     };
 
     let is_force_split = |sentence: &Sentence| match &opts.split_by_paragraph {
         None => false,
         Some(s) => match sentence.text() {
-            Some(ref text) => text == s,
-            None => false,
+            Ok(ref text) => text == s,
+            Err(_) => false,
         },
     };
 
@@ -190,10 +297,7 @@ async fn parse_book(
 
         if is_force_split(&s) {
             let current_part_len = {
-                let symbols: u32 = current_part
-                    .iter()
-                    .map(|s: &Sentence| s.info().size.symbols)
-                    .sum();
+                let symbols = symbols(&current_part);
                 (match current_part.len() as u32 {
                     sentence_count if sentence_count > 1 => symbols + sentence_count - 1,
                     sentence_count => sentence_count,
@@ -205,7 +309,7 @@ async fn parse_book(
                 parts.last_mut(),
             ) {
                 (true, Some(last)) => last.append(&mut current_part),
-                _ => parts.push(current_part.drain(..).collect::<Vec<_>>()),
+                _ => parts.push(std::mem::take(&mut current_part)),
             }
 
             is_last_force_splitted = true;
@@ -216,7 +320,7 @@ async fn parse_book(
             if chars_part < opts.min {
                 current_part.push(s);
             } else if chars_part + chars_sentence > opts.max {
-                let tmp = current_part.drain(..).collect::<Vec<_>>();
+                let tmp = std::mem::take(&mut current_part);
                 parts.push(tmp);
                 current_part.push(s);
             } else {
@@ -224,7 +328,7 @@ async fn parse_book(
             }
 
             if symbols(&current_part) > opts.max {
-                let tmp = current_part.drain(..).collect::<Vec<_>>();
+                let tmp = std::mem::take(&mut current_part);
                 parts.push(tmp);
             }
 
@@ -241,32 +345,51 @@ async fn parse_book(
 
     let (handle, tx) = process("Mapping into strings...");
 
-    let parts = parts
-        .iter()
-        .map(|p| p.iter().filter(|&s| s.text().is_some()));
+    let mut out = String::new();
+    let mut text = String::new();
 
-    let mut out = vec![];
-
-    for (pi, p) in parts.enumerate() {
+    for (pi, p) in parts.iter().enumerate() {
         let view_pi = pi + if opts.count_parts_from_zero { 1 } else { 0 };
         let replacer = |input_str: &str| input_str.replace("{}", &view_pi.to_string());
 
-        out.push(match &opts.parts_separator {
-            Some(Some(s)) => vec!["\r\n", &replacer(s), "\r\n"].join(""),
+        out.push_str(&match &opts.parts_separator {
+            Some(Some(s)) => ["\r\n", &replacer(s), "\r\n"].join(""),
             Some(None) => format!("\r\n## {} ##\r\n", view_pi),
             None => String::from("\r\n\r\n"),
         });
 
-        for (si, s) in p.enumerate() {
+        // A gap after a sentence that is not found in the original text contains that
+        // sentence, so such parts are joined as without the formatting
+        let preserve = opts.preserve_formatting && p.iter().all(|s| s.text_ref().is_some());
+
+        // Sentences whose text cannot be read are skipped
+        let mut si = 0;
+        for s in p {
+            text.clear();
+            let original = match preserve {
+                true => s.text_ref(),
+                false => None,
+            };
+            match original {
+                Some(original) => text.push_str(original),
+                None if s.write_text_into(&mut text).is_err() => continue,
+                None => (),
+            }
+
             if si > 0 {
-                out.push(String::from(if s.is_first() { "\r\n" } else { " " }));
+                let gap = match preserve {
+                    true => s.gap_before(),
+                    false => None,
+                };
+                out.push_str(gap.unwrap_or(if s.is_first() { "\r\n" } else { " " }));
             }
 
             if opts.view_index_sentence {
                 let info = s.info();
-                out.push(format!("({}:{}:{}) ", si, info.s_number, info.index));
+                out.push_str(&format!("({}:{}:{}) ", si, info.s_number, info.index));
             }
-            out.push(s.text().unwrap());
+            out.push_str(&text);
+            si += 1;
         }
     }
 
@@ -278,9 +401,7 @@ async fn parse_book(
         writer_status = Some(process(&format!("Writing into file: {}", path)));
     }
 
-    for data in out {
-        writer.write_all(data.as_bytes())?;
-    }
+    writer.write_all(out.as_bytes())?;
     writer.flush()?;
 
     if let Some((handle, tx)) = writer_status {
@@ -0,0 +1,121 @@
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+fn main() {
+    let target = env::var("TARGET").unwrap();
+
+    // Used to find the prebuilt library in the `lib/<target-triple>/` layout.
+    println!("cargo:rustc-env=BOOK_PARSE_TARGET={}", target);
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Features are additive: `link-static` takes precedence if both are enabled.
+    let kind = if env::var_os("CARGO_FEATURE_LINK_STATIC").is_some() {
+        "static"
+    } else if env::var_os("CARGO_FEATURE_LINK_DYNAMIC").is_some() {
+        "dylib"
+    } else {
+        return;
+    };
+
+    link(&target, kind);
+    generate_bindings();
+}
+
+/// Links the prebuilt library from `BOOK_PARSE_LIB_DIR` or `lib/<target-triple>/`.
+fn link(target: &str, kind: &str) {
+    println!("cargo:rerun-if-env-changed=BOOK_PARSE_LIB_DIR");
+
+    let dir = match env::var_os("BOOK_PARSE_LIB_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => root().join("lib").join(target),
+    };
+
+    let windows = target.contains("windows");
+    let files: &[&str] = match (kind, windows) {
+        ("static", true) => &["book_parse.lib", "libbook_parse.a"],
+        ("static", false) => &["libbook_parse.a"],
+        (_, true) => &["book_parse.dll.lib", "book_parse.lib", "book_parse.dll"],
+        (_, false) => &["libbook_parse.so", "libbook_parse.dylib"],
+    };
+
+    if !files.iter().any(|file| dir.join(file).is_file()) {
+        panic!(
+            "prebuilt `book_parse` library for `{}` linking is not found in `{}`, expected one of: {}",
+            kind,
+            dir.display(),
+            files.join(", ")
+        );
+    }
+
+    println!("cargo:rustc-link-search=native={}", dir.display());
+    println!("cargo:rustc-link-lib={}=book_parse", kind);
+
+    // Tests and examples of this package find the shared library at runtime, dependents
+    // do not get it and need the library on the loader path, see README
+    let os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if kind == "dylib" && !windows && os != "macos" && os != "ios" {
+        println!("cargo:rustc-link-arg=-Wl,-rpath,{}", dir.display());
+    }
+}
+
+/// Generates `extern "C"` declarations from `lib/book_parse.h`.
+fn generate_bindings() {
+    let header = root().join("lib").join("book_parse.h");
+    println!("cargo:rerun-if-changed={}", header.display());
+
+    let source = fs::read_to_string(&header)
+        .unwrap_or_else(|e| panic!("cannot read `{}`: {}", header.display(), e));
+
+    let mut out = String::from("extern \"C\" {\n");
+    for line in source.lines().map(str::trim) {
+        if line.starts_with("//") || line.starts_with("typedef") || !line.ends_with(");") {
+            continue;
+        }
+
+        let (signature, args) = line.trim_end_matches(");").split_once('(').unwrap();
+        let (ret, name) = signature.rsplit_once(' ').unwrap();
+
+        let args = args
+            .split(',')
+            .map(|arg| {
+                let arg = arg.trim();
+                let split = arg.rfind([' ', '*']).unwrap() + 1;
+                format!("{}: {}", &arg[split..], rust_type(&arg[..split]))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        write!(out, "    pub fn {}({})", name, args).unwrap();
+        if ret != "void" {
+            write!(out, " -> {}", rust_type(ret)).unwrap();
+        }
+        out.push_str(";\n");
+    }
+    out.push_str("}\n");
+
+    let path = Path::new(&env::var_os("OUT_DIR").unwrap()).join("book_parse.rs");
+    fs::write(path, out).unwrap();
+}
+
+fn rust_type(c_type: &str) -> &'static str {
+    match c_type.trim() {
+        "Book" => "BookRaw",
+        "BookInfo" => "BookInfo",
+        "ParagraphInfo" => "ParagraphInfo",
+        "SentenceInfo" => "SentenceInfo",
+        "ParagraphId" => "ParagraphId",
+        "SentenceId" => "SentenceId",
+        "unsigned int" => "c_uint",
+        "unsigned char *" => "*mut c_uchar",
+        other => panic!("unsupported C type in `book_parse.h`: `{}`", other),
+    }
+}
+
+/// Root of the repository, the prebuilt libraries are located in its `lib` directory.
+fn root() -> PathBuf {
+    Path::new(&env::var_os("CARGO_MANIFEST_DIR").unwrap()).join("../../..")
+}
@@ -0,0 +1,177 @@
+//! Parsing on the blocking pool of tokio and a stream of sentences.
+//!
+//! A native parse cannot be interrupted: a cancelled future returns at once, but the
+//! parse itself finishes in background. Chunked parsing skips chunks that are not
+//! started yet.
+
+use std::{
+    ops::ControlFlow,
+    os::raw::c_uint,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use tokio::{sync::watch, task};
+
+use crate::{
+    book::Book, chunked::ChunkedParser, error::Error, ffi::SentenceInfo, mapped, owned::OwnedBook,
+};
+
+/// Token to cancel async parsing, its clones cancel the same parsing
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl Default for CancelToken {
+    fn default() -> Self {
+        Self {
+            sender: Arc::new(watch::Sender::new(false)),
+        }
+    }
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Waits until the token is cancelled
+    pub async fn cancelled(&self) {
+        let mut receiver = self.sender.subscribe();
+        // The sender is alive while `self` is, so waiting cannot fail
+        let _ = receiver.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl<'a> Book<'a> {
+    /// Parses `text` with the native library on the blocking pool.
+    ///
+    /// Returns [`Error::Cancelled`] as soon as `cancel` is cancelled.
+    pub async fn parse_async<T: Into<Arc<str>>>(
+        text: T,
+        cancel: &CancelToken,
+    ) -> Result<OwnedBook, Error> {
+        let text = text.into();
+        spawn(cancel, move || {
+            Book::from_utf8(&text)?.into_owned(Arc::clone(&text))
+        })
+        .await
+    }
+}
+
+impl Book<'static> {
+    /// Memory-maps the file at `path` and parses it with the native library on the
+    /// blocking pool, see [`Book::from_path`].
+    ///
+    /// Returns [`Error::Cancelled`] as soon as `cancel` is cancelled.
+    pub async fn from_path_async<P: Into<PathBuf>>(
+        path: P,
+        cancel: &CancelToken,
+    ) -> Result<Self, Error> {
+        let path = path.into();
+        // A book is not `Send`, so it is created here from the parsed parts
+        let (wrapper, text) = spawn(cancel, move || mapped::parse(&path)).await?;
+        Ok(Book::from_mapped(wrapper, text))
+    }
+}
+
+impl ChunkedParser {
+    /// Parses `text` in chunks on the blocking pool and joins them into a single book.
+    ///
+    /// The progress callback is called as for [`Self::parse`]. Returns
+    /// [`Error::Cancelled`] as soon as `cancel` is cancelled and
+    /// [`Error::TextTooLarge`] if the text is longer than a single book can be.
+    pub async fn parse_async<T: Into<Arc<str>>>(
+        &self,
+        text: T,
+        cancel: &CancelToken,
+    ) -> Result<OwnedBook, Error> {
+        let text = text.into();
+        let token = cancel.clone();
+        let progress = self.progress.clone();
+        let parser = self
+            .clone()
+            .on_progress(move |p| match token.is_cancelled() {
+                true => ControlFlow::Break(()),
+                false => progress
+                    .as_ref()
+                    .map_or(ControlFlow::Continue(()), |f| f(p)),
+            });
+
+        spawn(cancel, move || {
+            parser.parse(&text)?.into_owned(Arc::clone(&text))
+        })
+        .await
+    }
+}
+
+/// Runs `parse` on the blocking pool until it finishes or `cancel` is cancelled
+async fn spawn<T, F>(cancel: &CancelToken, parse: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Error> + Send + 'static,
+{
+    if cancel.is_cancelled() {
+        return Err(Error::Cancelled);
+    }
+
+    let handle = task::spawn_blocking(parse);
+    tokio::select! {
+        result = handle => match result {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            // The runtime is shutting down
+            Err(_) => Err(Error::Cancelled),
+        },
+        _ = cancel.cancelled() => Err(Error::Cancelled),
+    }
+}
+
+impl OwnedBook {
+    /// Returns a stream of infos and texts of sentences, see [`SentenceStream`]
+    pub fn sentence_stream(&self) -> SentenceStream {
+        SentenceStream {
+            book: self.clone(),
+            next: 0,
+        }
+    }
+}
+
+/// Stream of infos and texts of sentences of [`OwnedBook`].
+///
+/// Sentences are already in memory, so every one is ready at once.
+#[derive(Debug, Clone)]
+pub struct SentenceStream {
+    book: OwnedBook,
+    next: c_uint,
+}
+
+impl Stream for SentenceStream {
+    type Item = (SentenceInfo, String);
+
+    fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let sentence = self.book.segmenter().sentences().get(self.next as usize);
+        let item = sentence.cloned();
+        if item.is_some() {
+            self.next += 1;
+        }
+        Poll::Ready(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.book.sentences_len() - self.next as usize;
+        (len, Some(len))
+    }
+}
@@ -1,40 +1,223 @@
-use std::sync::Arc;
+use std::{ops::Range, os::raw::c_uint, rc::Rc, sync::Arc};
 
 use crate::{
-    ffi::BookInfo,
+    error::Error,
+    ffi::{BookInfo, ParagraphId, SentenceId},
+    index::BookIndex,
+    iter::{Paragraphes, Sentences},
+    memory::MemorySegmenter,
+    owned::OwnedBook,
     paragraph::Paragraph,
+    segmenter::Segmenter,
     sentence::Sentence,
-    wrapper::{BookError, Wrapper},
+    source::Source,
+    verify::Report,
+    wrapper::Wrapper,
 };
 
 #[derive(Debug)]
 pub struct Book<'a> {
-    ffi: Arc<Wrapper>,
-    text: &'a str,
+    source: Rc<Source<'a>>,
+    info: BookInfo,
 }
 
 impl<'a> Book<'a> {
-    pub fn from_utf8(text: &'a str) -> Result<Self, Box<dyn std::error::Error>> {
-        let ffi = Arc::new(Wrapper::from_utf8(text)?);
-        let book_info = ffi.book_info();
+    pub fn from_utf8(text: &'a str) -> Result<Self, Error> {
+        Self::with_segmenter(Wrapper::from_utf8(text)?, text)
+    }
+
+    /// Parses `text` with the pure Rust segmentation, the native library is not used.
+    #[cfg(feature = "rust-segmenter")]
+    pub fn from_utf8_rust(text: &'a str) -> Result<Self, Error> {
+        use crate::rust_segmenter::RustSegmenter;
+
+        Self::with_segmenter(RustSegmenter::from_utf8(text)?, text)
+    }
+
+    /// Creates a book over the data of `segmenter` that was produced from `text`.
+    pub fn with_segmenter<S: Segmenter + 'static>(
+        segmenter: S,
+        text: &'a str,
+    ) -> Result<Self, Error> {
+        let book_info = segmenter.book_info();
         if book_info.size.bytes == 0 {
-            return Err(Box::new(BookError));
+            return Err(Error::EmptyInput);
         }
 
-        Ok(Self { ffi, text })
+        Ok(Self {
+            source: Rc::new(Source::new(Box::new(segmenter), text)),
+            info: book_info,
+        })
+    }
+
+    pub(crate) fn from_source(source: Rc<Source<'a>>) -> Self {
+        let info = source.segmenter.book_info();
+        Self { source, info }
+    }
+
+    /// Pulls all infos and texts of the book into memory and releases the native handle
+    /// (it is kept alive while any paragraph or sentence of this book exists).
+    pub fn materialize(self) -> Result<OwnedBook, Error> {
+        let text = Arc::from(&*self.source.text);
+        self.into_owned(text)
+    }
+
+    /// Materializes the book with `text` that equals to the text of the book
+    pub(crate) fn into_owned(self, text: Arc<str>) -> Result<OwnedBook, Error> {
+        let data = MemorySegmenter::from_segmenter(&*self.source.segmenter)?;
+        let spans = self.source.spans().clone();
+        Ok(OwnedBook::new(data, text, spans))
     }
 
     pub fn info(&self) -> BookInfo {
-        self.ffi.book_info()
+        self.info
+    }
+
+    /// Returns the original text of the book
+    pub fn text(&self) -> &str {
+        &self.source.text
+    }
+
+    /// Returns count of paragraphes in the book
+    pub fn paragraphes_len(&self) -> usize {
+        self.info.paragraphes as usize
+    }
+
+    /// Returns count of sentences in the book
+    pub fn sentences_len(&self) -> usize {
+        self.info.sentences as usize
+    }
+
+    /// Returns the paragraph by its unique `index` or `None` if it is out of range
+    pub fn paragraph(&self, index: ParagraphId) -> Option<Paragraph<'a>> {
+        match c_uint::from(index) < self.info.paragraphes {
+            true => Some(Paragraph::new(Rc::clone(&self.source), index)),
+            false => None,
+        }
+    }
+
+    /// Returns the sentence by its unique `index` or `None` if it is out of range
+    pub fn sentence(&self, index: SentenceId) -> Option<Sentence<'a>> {
+        match c_uint::from(index) < self.info.sentences {
+            true => Some(Sentence::new(Rc::clone(&self.source), index)),
+            false => None,
+        }
+    }
+
+    /// Returns infos of all paragraphes and sentences with prefix sums of their sizes.
+    ///
+    /// The index is built on first call, paragraphes and sentences take their infos from
+    /// it then instead of the segmenter.
+    pub fn index(&self) -> &BookIndex {
+        self.source.index()
+    }
+
+    /// Returns the paragraph whose span in the original text (see [`Paragraph::span`])
+    /// contains byte `offset`, `None` if it is in a gap between paragraphes or out of
+    /// the text.
+    ///
+    /// Spans are located on first call, a paragraph that cannot be found in the original
+    /// text is never returned.
+    pub fn paragraph_at_byte(&self, offset: usize) -> Option<Paragraph<'a>> {
+        let index = self.source.offsets().paragraph_at_byte(offset)?;
+        self.paragraph(index.into())
+    }
+
+    /// Returns the paragraph that contains `offset` in chars of the original text,
+    /// see [`Self::paragraph_at_byte`]
+    pub fn paragraph_at_char(&self, offset: usize) -> Option<Paragraph<'a>> {
+        let index = self.source.offsets().paragraph_at_char(offset)?;
+        self.paragraph(index.into())
+    }
+
+    /// Returns the sentence whose span in the original text (see [`Sentence::span`])
+    /// contains byte `offset`, `None` if it is in a gap between sentences or out of the
+    /// text, see [`Self::paragraph_at_byte`]
+    pub fn sentence_at_byte(&self, offset: usize) -> Option<Sentence<'a>> {
+        let index = self.source.offsets().sentence_at_byte(offset)?;
+        self.sentence(index.into())
+    }
+
+    /// Returns the sentence that contains `offset` in chars of the original text,
+    /// see [`Self::sentence_at_byte`]
+    pub fn sentence_at_char(&self, offset: usize) -> Option<Sentence<'a>> {
+        let index = self.source.offsets().sentence_at_char(offset)?;
+        self.sentence(index.into())
+    }
+
+    /// Rebuilds the text from sentences as the segmenter returns them (see [`Sentence::text`])
+    /// and the original gaps between them (see [`Sentence::gap_before`]). It equals the
+    /// original text byte-for-byte if the segmenter does not normalize sentences.
+    ///
+    /// A sentence that is not found in the original text is taken from there together
+    /// with the gaps around it, as is a sentence whose text cannot be read.
+    pub fn reconstruct(&self) -> String {
+        let text = &self.source.text;
+        let mut reconstructed = String::with_capacity(text.len());
+        let mut end = 0;
+
+        for (i, span) in self.source.spans().iter().enumerate() {
+            let span = match span {
+                Some(span) => span,
+                None => continue,
+            };
+            reconstructed.push_str(&text[end..span.start]);
+            let index = SentenceId::from(i as c_uint);
+            if self
+                .source
+                .segmenter
+                .write_sentence_text(index, &mut reconstructed)
+                .is_err()
+            {
+                reconstructed.push_str(&text[span.clone()]);
+            }
+            end = span.end;
+        }
+
+        reconstructed.push_str(&text[end..]);
+        reconstructed
+    }
+
+    /// Checks infos of all paragraphes and sentences against each other and the book info.
+    /// It does not read texts, see [`crate::Wrapper::paranoid`] for checks of them.
+    pub fn verify(&self) -> Report {
+        Report::new(&self.info, self.index())
+    }
+
+    /// Calls `f` with the index and the text of every sentence in order, the texts are
+    /// read into a single buffer. Stops at the first sentence whose text cannot be read.
+    pub fn for_each_sentence_text<F: FnMut(SentenceId, &str)>(
+        &self,
+        mut f: F,
+    ) -> Result<(), Error> {
+        let mut buf = String::new();
+        for i in 0..self.info.sentences {
+            buf.clear();
+            self.source
+                .segmenter
+                .write_sentence_text(i.into(), &mut buf)?;
+            f(i.into(), &buf);
+        }
+        Ok(())
+    }
+
+    pub fn paragraphes(&self) -> Paragraphes<'a> {
+        self.paragraphes_in(ParagraphId::from(0)..self.info.paragraphes.into())
+    }
+
+    pub fn sentences(&self) -> Sentences<'a> {
+        self.sentences_in(SentenceId::from(0)..self.info.sentences.into())
     }
 
-    pub fn paragraphes(&self) -> impl Iterator<Item = Paragraph> + '_ {
-        let book_info = self.info();
-        (0..book_info.paragraphes).map(move |i| Paragraph::new(Arc::clone(&self.ffi), i.into()))
+    /// Returns paragraphes with indexes in `range`, the part of the range that is out of
+    /// the book is skipped
+    pub fn paragraphes_in(&self, range: Range<ParagraphId>) -> Paragraphes<'a> {
+        Paragraphes::new(Rc::clone(&self.source), range)
     }
 
-    pub fn sentences(&self) -> impl Iterator<Item = Sentence> + '_ {
-        let book_info = self.info();
-        (0..book_info.sentences).map(move |i| Sentence::new(Arc::clone(&self.ffi), i.into()))
+    /// Returns sentences with indexes in `range`, the part of the range that is out of
+    /// the book is skipped
+    pub fn sentences_in(&self, range: Range<SentenceId>) -> Sentences<'a> {
+        Sentences::new(Rc::clone(&self.source), range)
     }
 }
@@ -0,0 +1,377 @@
+//! On-disk cache of parsed books keyed by a hash of their text.
+//!
+//! An entry keeps infos of paragraphes and sentences, byte ranges of sentences in the
+//! text and only those texts that cannot be restored from the text itself.
+//! Numbers are little-endian `u32`, an entry has the following layout:
+//!
+//! * header: magic, format version, the wrapper version and the library fingerprint
+//!   (both as length-prefixed strings), length of the text in bytes;
+//! * book info: paragraphes, sentences, size in bytes and symbols;
+//! * paragraphes: first sentence, sentences, size, text;
+//! * sentences: number in paragraph, paragraph index, size, span, text.
+//!
+//! Indexes of paragraphes and sentences are their positions. A span is a `0` byte or
+//! a `1` byte followed by its start and end. A text is a `0` byte if it is restored
+//! (a sentence equals its span in the text, a paragraph equals its sentences joined
+//! with a space) or a `1` byte followed by a length-prefixed string.
+
+use std::{
+    env, fs,
+    io::{self, Write},
+    os::raw::c_uint,
+    path::{Path, PathBuf},
+    process,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::UNIX_EPOCH,
+};
+
+use sha2::{Digest, Sha256};
+
+use crate::{
+    book::Book,
+    error::Error,
+    ffi::*,
+    library::{init, loaded_from},
+    locate::{candidates, Candidate},
+    memory::MemorySegmenter,
+    source::{Source, Spans},
+};
+
+const MAGIC: &[u8; 4] = b"BPC\0";
+const FORMAT: u32 = 1;
+const EXTENSION: &str = "bpc";
+
+/// Directory with cached books.
+///
+/// Entries are invalidated when the native library file (its path, size or modification
+/// time) or the version of the wrapper changes. Books are not cached if the library is
+/// loaded from the system search path, such a library cannot be identified.
+#[derive(Debug, Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    library: Option<PathBuf>,
+}
+
+impl Cache {
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            library: None,
+        }
+    }
+
+    /// Sets the path of the native library, it is passed to [`init`] on the first miss.
+    pub fn library<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.library = Some(path.into());
+        self
+    }
+
+    /// Returns the cached book or parses `text` with the native library and stores it.
+    ///
+    /// A cached book does not load the native library. Failures of writing the entry
+    /// are ignored, use [`Self::store`] to handle them.
+    pub fn book<'a>(&self, text: &'a str) -> Result<Book<'a>, Error> {
+        if let Some(book) = self.load(text) {
+            return Ok(book);
+        }
+
+        if let Some(ref path) = self.library {
+            match init(path) {
+                Ok(()) | Err(Error::AlreadyInitialized) => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let book = Book::from_utf8(text)?;
+        let _ = self.store(&book);
+        Ok(book)
+    }
+
+    /// Returns the cached book of `text` or `None` if there is no valid entry
+    pub fn load<'a>(&self, text: &'a str) -> Option<Book<'a>> {
+        let fingerprint = fingerprint(&self.library_path(true)?)?;
+        let bytes = fs::read(self.path(text)).ok()?;
+        let (segmenter, spans) = decode(&bytes, text, &fingerprint)?;
+        let source = Rc::new(Source::with_spans(Box::new(segmenter), text, spans));
+        Some(Book::from_source(source))
+    }
+
+    /// Writes the entry of `book` parsed by the loaded library, it replaces an existing one.
+    ///
+    /// Returns an error of [`io::ErrorKind::Unsupported`] kind if the library cannot be
+    /// identified, see [`Cache`].
+    pub fn store(&self, book: &Book) -> io::Result<()> {
+        let fingerprint = self
+            .library_path(false)
+            .and_then(|path| fingerprint(&path))
+            .ok_or_else(|| {
+                let reason = "the native library cannot be identified";
+                io::Error::new(io::ErrorKind::Unsupported, reason)
+            })?;
+        let bytes = encode(book, &fingerprint)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+
+        // Unique for concurrent writers in this and other processes
+        static WRITES: AtomicUsize = AtomicUsize::new(0);
+        let write = WRITES.fetch_add(1, Ordering::Relaxed);
+
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(book.text());
+        let tmp = path.with_extension(format!("{}.{}-{}", EXTENSION, process::id(), write));
+        fs::File::create(&tmp)?.write_all(&bytes)?;
+        fs::rename(&tmp, &path)
+    }
+
+    fn path(&self, text: &str) -> PathBuf {
+        let hash = Sha256::digest(text.as_bytes());
+        let name = hash
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>();
+        self.dir.join(name).with_extension(EXTENSION)
+    }
+
+    /// Returns the file of the library that parses books, the executable itself if the
+    /// library is linked at build time. If the library is not loaded yet and `predict`
+    /// is set, returns the file it would be loaded from, as [`Self::book`] loads it.
+    /// Returns `None` if the library is found in the system search path.
+    fn library_path(&self, predict: bool) -> Option<PathBuf> {
+        if cfg!(any(feature = "link-static", feature = "link-dynamic")) {
+            return env::current_exe().ok();
+        }
+
+        let candidate = match loaded_from() {
+            Some(candidate) => candidate,
+            // `init` tries the given path only
+            None if predict => match self.library {
+                Some(ref path) => Candidate::Explicit(path.clone()),
+                None => candidates(None)
+                    .into_iter()
+                    .find(|c| Path::new(c.as_os_str()).is_file())?,
+            },
+            None => return None,
+        };
+
+        match candidate {
+            Candidate::System(_) => None,
+            candidate => Some(PathBuf::from(candidate.as_os_str())),
+        }
+    }
+}
+
+/// Identifies the library file by its path, size and modification time
+fn fingerprint(path: &Path) -> Option<String> {
+    let meta = fs::metadata(path).ok()?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    Some(format!("{}:{}:{}", path.display(), meta.len(), modified))
+}
+
+fn encode(book: &Book, fingerprint: &str) -> Result<Vec<u8>, Error> {
+    let text = book.text();
+    let info = book.info();
+    let mut out = Writer(Vec::with_capacity(64 + info.sentences as usize * 32));
+
+    out.0.extend_from_slice(MAGIC);
+    out.u32(FORMAT);
+    out.string(env!("CARGO_PKG_VERSION"));
+    out.string(fingerprint);
+    out.len(text.len());
+
+    out.u32(info.paragraphes);
+    out.u32(info.sentences);
+    out.size(info.size);
+
+    let sentences = book
+        .sentences()
+        .map(|s| Ok((s.info(), s.text()?, s.span())))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    for paragraph in book.paragraphes() {
+        let info = paragraph.info();
+        out.u32(info.sentence_first.into());
+        out.u32(info.sentences);
+        out.size(info.size);
+
+        let first = c_uint::from(info.sentence_first) as usize;
+        let last = first.saturating_add(info.sentences as usize);
+        let joined = sentences
+            .get(first..last)
+            .map(|s| s.iter().map(|s| s.1.as_str()).collect::<Vec<_>>().join(" "));
+        out.text(&paragraph.text()?, joined.as_deref());
+    }
+
+    for (info, sentence, span) in &sentences {
+        out.u32(info.s_number);
+        out.u32(info.p_index.into());
+        out.size(info.size);
+        match span {
+            Some(span) => {
+                out.0.push(1);
+                out.len(span.start);
+                out.len(span.end);
+            }
+            None => out.0.push(0),
+        }
+        out.text(sentence, span.clone().and_then(|s| text.get(s)));
+    }
+
+    Ok(out.0)
+}
+
+fn decode(bytes: &[u8], text: &str, fingerprint: &str) -> Option<(MemorySegmenter, Spans)> {
+    let mut r = Reader(bytes);
+
+    if r.take(MAGIC.len())? != MAGIC
+        || r.u32()? != FORMAT
+        || r.string()? != env!("CARGO_PKG_VERSION")
+        || r.string()? != fingerprint
+        || r.u32()? as usize != text.len()
+    {
+        return None;
+    }
+
+    let info = BookInfo {
+        paragraphes: r.u32()?,
+        sentences: r.u32()?,
+        size: r.size()?,
+    };
+
+    let mut paragraphes = vec![];
+    for index in 0..info.paragraphes {
+        let info = ParagraphInfo {
+            index: index.into(),
+            sentence_first: r.u32()?.into(),
+            sentences: r.u32()?,
+            size: r.size()?,
+        };
+        // Texts joined from sentences are restored below
+        paragraphes.push((info, r.text()?.map(String::from)));
+    }
+
+    let mut sentences = vec![];
+    let mut spans = vec![];
+    for index in 0..info.sentences {
+        let info = SentenceInfo {
+            index: index.into(),
+            s_number: r.u32()?,
+            p_index: r.u32()?.into(),
+            size: r.size()?,
+        };
+        let span = match r.take(1)?[0] {
+            0 => None,
+            _ => Some(r.u32()? as usize..r.u32()? as usize),
+        };
+        if span.as_ref().is_some_and(|s| text.get(s.clone()).is_none()) {
+            return None;
+        }
+        let sentence = match r.text()? {
+            Some(sentence) => String::from(sentence),
+            None => String::from(text.get(span.clone()?)?),
+        };
+        sentences.push((info, sentence));
+        spans.push(span);
+    }
+
+    if !r.0.is_empty() {
+        return None;
+    }
+
+    let paragraphes = paragraphes
+        .into_iter()
+        .map(|(info, paragraph)| {
+            let paragraph = match paragraph {
+                Some(paragraph) => paragraph,
+                None => {
+                    let first = c_uint::from(info.sentence_first) as usize;
+                    let last = first.checked_add(info.sentences as usize)?;
+                    let sentences = sentences.get(first..last)?;
+                    sentences
+                        .iter()
+                        .map(|s| s.1.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+            };
+            Some((info, paragraph))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let segmenter = MemorySegmenter::new(info, paragraphes, sentences);
+    Some((segmenter, spans.into()))
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.0.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Byte lengths and offsets fit into `u32` as the native library takes `c_uint` length
+    fn len(&mut self, value: usize) {
+        self.u32(value as u32)
+    }
+
+    fn size(&mut self, size: StringSize) {
+        self.u32(size.bytes);
+        self.u32(size.symbols);
+    }
+
+    fn string(&mut self, value: &str) {
+        self.len(value.len());
+        self.0.extend_from_slice(value.as_bytes());
+    }
+
+    /// Writes `value` unless it equals to `restored`
+    fn text(&mut self, value: &str, restored: Option<&str>) {
+        if restored == Some(value) {
+            self.0.push(0);
+        } else {
+            self.0.push(1);
+            self.string(value);
+        }
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.0.len() < len {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.take(4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn size(&mut self) -> Option<StringSize> {
+        Some(StringSize {
+            bytes: self.u32()?,
+            symbols: self.u32()?,
+        })
+    }
+
+    fn string(&mut self) -> Option<&'a str> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.take(len)?).ok()
+    }
+
+    /// Returns `None` inside if the text should be restored
+    fn text(&mut self) -> Option<Option<&'a str>> {
+        match self.take(1)?[0] {
+            0 => Some(None),
+            _ => self.string().map(Some),
+        }
+    }
+}
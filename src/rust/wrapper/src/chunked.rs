@@ -0,0 +1,487 @@
+//! Parsing of large texts in chunks on several threads.
+//!
+//! The text is cut right after line breaks, so every chunk contains whole paragraphes,
+//! and every chunk is parsed by its own native handle. Ids and offsets of the stitched
+//! book are 64-bit, the text is not limited by the native `c_uint` length.
+
+use std::{
+    convert::TryFrom,
+    fmt,
+    ops::{ControlFlow, Range},
+    os::raw::c_uint,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    book::Book,
+    error::Error,
+    ffi::{BookInfo, ParagraphInfo, SentenceInfo, StringSize},
+    memory::MemorySegmenter,
+    owned::OwnedBook,
+    paragraph::Paragraph,
+    sentence::Sentence,
+    wrapper::Wrapper,
+};
+
+/// Default size of a chunk in bytes
+const CHUNK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Callback of [`ChunkedParser::on_progress`]
+type ProgressFn = dyn Fn(Progress) -> ControlFlow<()> + Send + Sync;
+
+/// Progress of [`ChunkedParser`], it is reported after every parsed chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Count of parsed chunks
+    pub chunks_done: usize,
+    /// Count of all chunks of the text
+    pub chunks: usize,
+    /// Bytes of the text in parsed chunks
+    pub bytes_done: usize,
+    /// Length of the whole text in bytes
+    pub bytes: usize,
+}
+
+/// Parser that cuts a text into chunks and parses them concurrently
+#[derive(Clone)]
+pub struct ChunkedParser {
+    chunk_size: usize,
+    threads: usize,
+    pub(crate) progress: Option<Arc<ProgressFn>>,
+}
+
+impl fmt::Debug for ChunkedParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChunkedParser")
+            .field("chunk_size", &self.chunk_size)
+            .field("threads", &self.threads)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl Default for ChunkedParser {
+    fn default() -> Self {
+        Self {
+            chunk_size: CHUNK_SIZE,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            progress: None,
+        }
+    }
+}
+
+impl ChunkedParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimal size of a chunk in bytes, a chunk is extended up to the end of its
+    /// last paragraph. It is limited by the maximal length the native library accepts.
+    pub fn chunk_size(mut self, bytes: usize) -> Self {
+        self.chunk_size = bytes.clamp(1, c_uint::MAX as usize);
+        self
+    }
+
+    /// Sets how many chunks are parsed at the same time
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Sets a callback that is called after every parsed chunk from the parsing thread.
+    ///
+    /// Parsing stops with [`Error::Cancelled`] as soon as the callback breaks, chunks
+    /// that are being parsed at this moment are finished first.
+    pub fn on_progress<F>(mut self, progress: F) -> Self
+    where
+        F: Fn(Progress) -> ControlFlow<()> + Send + Sync + 'static,
+    {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    /// Parses `text` in chunks.
+    ///
+    /// Returns [`Error::TextTooLarge`] if a paragraph is longer than the native library
+    /// accepts and [`Error::EmptyInput`] if the text contains nothing to parse.
+    pub fn parse<'a>(&self, text: &'a str) -> Result<ChunkedBook<'a>, Error> {
+        let ranges = self.split(text)?;
+        let wrappers = self.parse_chunks(text, &ranges)?;
+
+        let mut chunks = vec![];
+        let (mut first_paragraph, mut first_sentence) = (0, 0);
+
+        for (range, wrapper) in ranges.into_iter().zip(wrappers) {
+            // A chunk of whitespaces only has nothing to parse
+            let book = match Book::with_segmenter(wrapper, &text[range.clone()]) {
+                Err(Error::EmptyInput) => continue,
+                book => book?,
+            };
+
+            let info = book.info();
+            chunks.push(Chunk::new(
+                range.start as u64,
+                first_paragraph,
+                first_sentence,
+                book,
+            ));
+            first_paragraph += u64::from(info.paragraphes);
+            first_sentence += u64::from(info.sentences);
+        }
+
+        match chunks.is_empty() {
+            true => Err(Error::EmptyInput),
+            false => Ok(ChunkedBook {
+                text,
+                chunks,
+                paragraphes: first_paragraph,
+                sentences: first_sentence,
+            }),
+        }
+    }
+
+    /// Returns byte ranges of chunks, every one ends right after a line break or at the end
+    fn split(&self, text: &str) -> Result<Vec<Range<usize>>, Error> {
+        let mut ranges = vec![];
+        let mut start = 0;
+
+        while start < text.len() {
+            let min_end = start.saturating_add(self.chunk_size).min(text.len());
+            let end = text.as_bytes()[min_end..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(text.len(), |i| min_end + i + 1);
+
+            let len = end - start;
+            if c_uint::try_from(len).is_err() {
+                return Err(Error::TextTooLarge { len });
+            }
+
+            ranges.push(start..end);
+            start = end;
+        }
+
+        Ok(ranges)
+    }
+
+    /// Parses every chunk by its own native handle, workers take chunks in order
+    fn parse_chunks(&self, text: &str, ranges: &[Range<usize>]) -> Result<Vec<Wrapper>, Error> {
+        let next = AtomicUsize::new(0);
+        let stopped = AtomicBool::new(false);
+        let progress = Progress {
+            chunks_done: 0,
+            chunks: ranges.len(),
+            bytes_done: 0,
+            bytes: text.len(),
+        };
+        let results = (0..ranges.len()).map(|_| None).collect::<Vec<_>>();
+        let state = Mutex::new((results, progress));
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(ranges.len()) {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let range = match ranges.get(i) {
+                        Some(_) if stopped.load(Ordering::Relaxed) => break,
+                        Some(range) => range.clone(),
+                        None => break,
+                    };
+
+                    let len = range.len();
+                    let wrapper = Wrapper::from_utf8(&text[range]);
+
+                    // The lock also keeps reported progress in order
+                    let mut state = state.lock().unwrap();
+                    let (results, progress) = &mut *state;
+                    results[i] = Some(wrapper);
+                    if let Some(ref callback) = self.progress {
+                        progress.chunks_done += 1;
+                        progress.bytes_done += len;
+                        if callback(*progress).is_break() {
+                            stopped.store(true, Ordering::Relaxed);
+                        }
+                    }
+                });
+            }
+        });
+
+        if stopped.into_inner() {
+            return Err(Error::Cancelled);
+        }
+
+        state
+            .into_inner()
+            .unwrap()
+            .0
+            .into_iter()
+            .map(|result| result.expect("every chunk is parsed"))
+            .collect()
+    }
+}
+
+/// Part of [`ChunkedBook`] parsed by a single native handle
+#[derive(Debug)]
+pub struct Chunk<'a> {
+    offset: u64,
+    first_paragraph: u64,
+    first_sentence: u64,
+    book: Book<'a>,
+}
+
+impl<'a> Chunk<'a> {
+    pub(crate) fn new(
+        offset: u64,
+        first_paragraph: u64,
+        first_sentence: u64,
+        book: Book<'a>,
+    ) -> Self {
+        Self {
+            offset,
+            first_paragraph,
+            first_sentence,
+            book,
+        }
+    }
+
+    /// Returns byte offset of the chunk in the whole text
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the global id of the first paragraph of the chunk
+    pub fn first_paragraph(&self) -> u64 {
+        self.first_paragraph
+    }
+
+    /// Returns the global id of the first sentence of the chunk
+    pub fn first_sentence(&self) -> u64 {
+        self.first_sentence
+    }
+
+    /// Returns the chunk as a book, its ids and offsets are local to the chunk
+    pub fn book(&self) -> &Book<'a> {
+        &self.book
+    }
+
+    /// Returns paragraphes of the chunk with global ids and offsets
+    pub fn paragraphes(&self) -> impl Iterator<Item = GlobalParagraph<'a>> + '_ {
+        self.book
+            .paragraphes()
+            .map(move |p| self.global_paragraph(p))
+    }
+
+    /// Returns sentences of the chunk with global ids and offsets
+    pub fn sentences(&self) -> impl Iterator<Item = GlobalSentence<'a>> + '_ {
+        self.book.sentences().map(move |s| self.global_sentence(s))
+    }
+
+    pub(crate) fn global_paragraph(&self, local: Paragraph<'a>) -> GlobalParagraph<'a> {
+        GlobalParagraph {
+            id: self.first_paragraph + u64::from(c_uint::from(local.info().index)),
+            first_sentence: self.first_sentence
+                + u64::from(c_uint::from(local.info().sentence_first)),
+            offset: self.offset,
+            paragraph: local,
+        }
+    }
+
+    pub(crate) fn global_sentence(&self, local: Sentence<'a>) -> GlobalSentence<'a> {
+        GlobalSentence {
+            id: self.first_sentence + u64::from(c_uint::from(local.info().index)),
+            paragraph_id: self.first_paragraph + u64::from(c_uint::from(local.info().p_index)),
+            offset: self.offset,
+            sentence: local,
+        }
+    }
+}
+
+/// Book stitched from chunks parsed separately, see [`ChunkedParser`]
+#[derive(Debug)]
+pub struct ChunkedBook<'a> {
+    text: &'a str,
+    chunks: Vec<Chunk<'a>>,
+    paragraphes: u64,
+    sentences: u64,
+}
+
+impl<'a> ChunkedBook<'a> {
+    /// Parses `text` with the default [`ChunkedParser`]
+    pub fn from_utf8(text: &'a str) -> Result<Self, Error> {
+        ChunkedParser::new().parse(text)
+    }
+
+    /// Returns the original text of the book
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn chunks(&self) -> &[Chunk<'a>] {
+        &self.chunks
+    }
+
+    /// Returns count of paragraphes in the book
+    pub fn paragraphes_len(&self) -> u64 {
+        self.paragraphes
+    }
+
+    /// Returns count of sentences in the book
+    pub fn sentences_len(&self) -> u64 {
+        self.sentences
+    }
+
+    /// Returns the paragraph by its global `id` or `None` if it is out of range
+    pub fn paragraph(&self, id: u64) -> Option<GlobalParagraph<'a>> {
+        let i = self
+            .chunks
+            .partition_point(|c| c.first_paragraph <= id)
+            .checked_sub(1)?;
+        let chunk = &self.chunks[i];
+        let local = c_uint::try_from(id - chunk.first_paragraph).ok()?;
+        Some(chunk.global_paragraph(chunk.book.paragraph(local.into())?))
+    }
+
+    /// Returns the sentence by its global `id` or `None` if it is out of range
+    pub fn sentence(&self, id: u64) -> Option<GlobalSentence<'a>> {
+        let i = self
+            .chunks
+            .partition_point(|c| c.first_sentence <= id)
+            .checked_sub(1)?;
+        let chunk = &self.chunks[i];
+        let local = c_uint::try_from(id - chunk.first_sentence).ok()?;
+        Some(chunk.global_sentence(chunk.book.sentence(local.into())?))
+    }
+
+    pub fn paragraphes(&self) -> impl Iterator<Item = GlobalParagraph<'a>> + '_ {
+        self.chunks.iter().flat_map(Chunk::paragraphes)
+    }
+
+    pub fn sentences(&self) -> impl Iterator<Item = GlobalSentence<'a>> + '_ {
+        self.chunks.iter().flat_map(Chunk::sentences)
+    }
+
+    /// Pulls all chunks into a single [`OwnedBook`] and releases their native handles.
+    ///
+    /// Returns [`Error::TextTooLarge`] if the text is longer than a single book can be.
+    pub fn materialize(self) -> Result<OwnedBook, Error> {
+        let text = self.text.into();
+        self.into_owned(text)
+    }
+
+    /// Materializes the book with `text` that equals to the text of the book
+    pub(crate) fn into_owned(self, text: Arc<str>) -> Result<OwnedBook, Error> {
+        let too_large = || Error::TextTooLarge { len: text.len() };
+        let global = |id: u64| c_uint::try_from(id).map_err(|_| too_large());
+        let sum = |a: c_uint, b: c_uint| a.checked_add(b).ok_or_else(too_large);
+
+        let mut info = BookInfo {
+            paragraphes: global(self.paragraphes)?,
+            sentences: global(self.sentences)?,
+            size: StringSize::default(),
+        };
+        let mut paragraphes = Vec::with_capacity(info.paragraphes as usize);
+        let mut sentences = Vec::with_capacity(info.sentences as usize);
+        let mut spans = Vec::with_capacity(info.sentences as usize);
+
+        for (i, chunk) in self.chunks.iter().enumerate() {
+            // Chunks are joined like paragraphes, with a single separator
+            let size = chunk.book.info().size;
+            let separator = (i > 0) as c_uint;
+            info.size.bytes = sum(info.size.bytes, sum(size.bytes, separator)?)?;
+            info.size.symbols = sum(info.size.symbols, sum(size.symbols, separator)?)?;
+
+            for paragraph in chunk.paragraphes() {
+                let local = paragraph.paragraph.info();
+                let info = ParagraphInfo {
+                    index: global(paragraph.id)?.into(),
+                    sentence_first: global(paragraph.first_sentence)?.into(),
+                    ..local
+                };
+                paragraphes.push((info, paragraph.paragraph.text()?));
+            }
+
+            for sentence in chunk.sentences() {
+                let local = sentence.sentence.info();
+                let info = SentenceInfo {
+                    index: global(sentence.id)?.into(),
+                    p_index: global(sentence.paragraph_id)?.into(),
+                    ..local
+                };
+                sentences.push((info, sentence.sentence.text()?));
+                spans.push(
+                    sentence
+                        .span()
+                        .map(|span| span.start as usize..span.end as usize),
+                );
+            }
+        }
+
+        let data = MemorySegmenter::new(info, paragraphes, sentences);
+        Ok(OwnedBook::new(data, text, spans.into()))
+    }
+}
+
+/// Paragraph of [`ChunkedBook`] with global ids and offsets
+#[derive(Debug)]
+pub struct GlobalParagraph<'a> {
+    id: u64,
+    first_sentence: u64,
+    offset: u64,
+    paragraph: Paragraph<'a>,
+}
+
+impl<'a> GlobalParagraph<'a> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the global id of the first sentence of the paragraph
+    pub fn first_sentence(&self) -> u64 {
+        self.first_sentence
+    }
+
+    /// Returns byte range of the paragraph in the whole text, see [`Paragraph::span`]
+    pub fn span(&self) -> Option<Range<u64>> {
+        let span = self.paragraph.span()?;
+        Some(self.offset + span.start as u64..self.offset + span.end as u64)
+    }
+
+    /// Returns the paragraph of its chunk, its ids and offsets are local to the chunk
+    pub fn local(&self) -> &Paragraph<'a> {
+        &self.paragraph
+    }
+}
+
+/// Sentence of [`ChunkedBook`] with global ids and offsets
+#[derive(Debug)]
+pub struct GlobalSentence<'a> {
+    id: u64,
+    paragraph_id: u64,
+    offset: u64,
+    sentence: Sentence<'a>,
+}
+
+impl<'a> GlobalSentence<'a> {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the global id of the paragraph that contains the sentence
+    pub fn paragraph_id(&self) -> u64 {
+        self.paragraph_id
+    }
+
+    /// Returns byte range of the sentence in the whole text, see [`Sentence::span`]
+    pub fn span(&self) -> Option<Range<u64>> {
+        let span = self.sentence.span()?;
+        Some(self.offset + span.start as u64..self.offset + span.end as u64)
+    }
+
+    /// Returns the sentence of its chunk, its ids and offsets are local to the chunk
+    pub fn local(&self) -> &Sentence<'a> {
+        &self.sentence
+    }
+}
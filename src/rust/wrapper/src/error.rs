@@ -0,0 +1,121 @@
+use std::{fmt::Display, io, str::Utf8Error};
+
+use crate::{
+    ffi::{ParagraphId, SentenceId},
+    locate::{library_filename, Candidate},
+};
+
+/// Errors of the wrapper
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// The native library cannot be loaded from any known location
+    LibraryNotFound {
+        /// Every tried location with the reason of its failure
+        tried: Vec<(Candidate, String)>,
+    },
+    /// The native library was loaded, but it does not export a required function
+    MissingSymbol { name: &'static str, reason: String },
+    /// The native library is already loaded and cannot be replaced
+    AlreadyInitialized,
+    /// The native library returned a null `Book` handle
+    NullBook,
+    /// The text contains nothing to parse
+    EmptyInput,
+    /// The text (or a paragraph of it for chunked parsing) is longer than the native library
+    /// can accept (`c_uint::MAX` bytes)
+    TextTooLarge { len: usize },
+    /// The native library returned a text that is not valid UTF-8
+    InvalidUtf8(Utf8Error),
+    /// The native library wrote less bytes of a text than its size reports
+    ShortWrite { expected: usize, written: usize },
+    /// The native library wrote past the reported size of a text
+    LongWrite { expected: usize },
+    /// The text has another count of symbols than its size reports
+    SymbolsMismatch { expected: u32, actual: usize },
+    /// Infos reported by the native library contradict each other (paranoid mode only)
+    InconsistentInfo { reason: String },
+    /// Parsing was stopped by a progress callback or a cancellation token
+    Cancelled,
+    /// The text cannot be read from its source, invalid UTF-8 is reported as
+    /// [`io::ErrorKind::InvalidData`]
+    Io { kind: io::ErrorKind, reason: String },
+    /// The paragraph does not exist in the book
+    ParagraphOutOfRange { index: ParagraphId, len: u32 },
+    /// The sentence does not exist in the book
+    SentenceOutOfRange { index: SentenceId, len: u32 },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::LibraryNotFound { tried } => {
+                write!(f, "Native library `{}` was not found", library_filename())?;
+                for (candidate, reason) in tried {
+                    write!(f, "\n  tried: {}: {}", candidate, reason)?;
+                }
+                Ok(())
+            }
+            Error::MissingSymbol { name, reason } => {
+                write!(f, "Symbol `{}` is missing: {}", name, reason)
+            }
+            Error::AlreadyInitialized => write!(f, "Native library is already loaded"),
+            Error::NullBook => write!(f, "Native library returned a null book"),
+            Error::EmptyInput => write!(f, "The text contains nothing to parse"),
+            Error::TextTooLarge { len } => write!(
+                f,
+                "The text is too large: {} bytes, maximum is {} bytes",
+                len,
+                u32::MAX
+            ),
+            Error::InvalidUtf8(e) => write!(f, "Native library returned invalid UTF-8: {}", e),
+            Error::ShortWrite { expected, written } => write!(
+                f,
+                "Native library wrote {} bytes of a text instead of {}",
+                written, expected
+            ),
+            Error::LongWrite { expected } => write!(
+                f,
+                "Native library wrote past the end of a text of {} bytes",
+                expected
+            ),
+            Error::SymbolsMismatch { expected, actual } => write!(
+                f,
+                "The text has {} symbols, native library reported {}",
+                actual, expected
+            ),
+            Error::InconsistentInfo { reason } => {
+                write!(f, "Native library reported inconsistent info: {}", reason)
+            }
+            Error::Cancelled => write!(f, "Parsing was cancelled"),
+            Error::Io { reason, .. } => write!(f, "The text cannot be read: {}", reason),
+            Error::ParagraphOutOfRange { index, len } => write!(
+                f,
+                "Paragraph {} is out of range, the book has {} paragraphes",
+                index, len
+            ),
+            Error::SentenceOutOfRange { index, len } => write!(
+                f,
+                "Sentence {} is out of range, the book has {} sentences",
+                index, len
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io {
+            kind: e.kind(),
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
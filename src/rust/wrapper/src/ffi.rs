@@ -7,6 +7,8 @@ use std::{
 pub type BookRaw = *mut c_void;
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(C)]
 pub struct ParagraphId(c_uint);
 
@@ -29,6 +31,8 @@ impl Display for ParagraphId {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 #[repr(C)]
 pub struct SentenceId(c_uint);
 
@@ -51,6 +55,7 @@ impl Display for SentenceId {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct StringSize {
     /// Размер UTF-8 строки в байтах
@@ -85,6 +90,7 @@ impl Add for StringSize {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct BookInfo {
     /// Количество абзацев в книге
@@ -96,6 +102,7 @@ pub struct BookInfo {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct ParagraphInfo {
     /// Порядковый номер абзаца в книге
@@ -109,6 +116,7 @@ pub struct ParagraphInfo {
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SentenceInfo {
     /// Порядковый номер предложения в книге
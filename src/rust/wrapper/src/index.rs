@@ -0,0 +1,125 @@
+//! Infos of all paragraphes and sentences retrieved at once, with prefix sums of their sizes.
+
+use std::{convert::TryFrom, ops::Range, os::raw::c_uint};
+
+use crate::{
+    ffi::{ParagraphId, ParagraphInfo, SentenceId, SentenceInfo, StringSize},
+    segmenter::Segmenter,
+};
+
+/// In-memory index of a book, see [`crate::Book::index`]
+#[derive(Debug, Clone, Default)]
+pub struct BookIndex {
+    paragraphes: Vec<ParagraphInfo>,
+    sentences: Vec<SentenceInfo>,
+    /// `paragraph_sums[i]` is the size of paragraphes before `i`, without separators
+    paragraph_sums: Vec<Sum>,
+    /// `sentence_sums[i]` is the size of sentences before `i`, without separators
+    sentence_sums: Vec<Sum>,
+}
+
+/// Sum of sizes, it is 64-bit to not overflow on sizes reported by a broken segmenter
+#[derive(Debug, Clone, Copy, Default)]
+struct Sum {
+    bytes: u64,
+    symbols: u64,
+}
+
+impl BookIndex {
+    pub(crate) fn new(segmenter: &dyn Segmenter) -> Self {
+        let info = segmenter.book_info();
+        let paragraphes = (0..info.paragraphes)
+            .map(|i| segmenter.paragraph_info(i.into()))
+            .collect::<Vec<_>>();
+        let sentences = (0..info.sentences)
+            .map(|i| segmenter.sentence_info(i.into()))
+            .collect::<Vec<_>>();
+
+        Self {
+            paragraph_sums: sums(paragraphes.iter().map(|p| p.size)),
+            sentence_sums: sums(sentences.iter().map(|s| s.size)),
+            paragraphes,
+            sentences,
+        }
+    }
+
+    pub fn paragraphes(&self) -> &[ParagraphInfo] {
+        &self.paragraphes
+    }
+
+    pub fn sentences(&self) -> &[SentenceInfo] {
+        &self.sentences
+    }
+
+    pub fn paragraph_info(&self, index: ParagraphId) -> Option<ParagraphInfo> {
+        self.paragraphes.get(c_uint::from(index) as usize).copied()
+    }
+
+    pub fn sentence_info(&self, index: SentenceId) -> Option<SentenceInfo> {
+        self.sentences.get(c_uint::from(index) as usize).copied()
+    }
+
+    /// Returns total size of paragraphes in `range` without separators between them,
+    /// it saturates at `c_uint::MAX`. The part of the range that is out of the book is
+    /// skipped.
+    pub fn paragraphes_size(&self, range: Range<ParagraphId>) -> StringSize {
+        saturate(difference(&self.paragraph_sums, range))
+    }
+
+    /// Returns total size of sentences in `range` without separators between them,
+    /// it saturates at `c_uint::MAX`. The part of the range that is out of the book is
+    /// skipped.
+    pub fn sentences_size(&self, range: Range<SentenceId>) -> StringSize {
+        saturate(difference(&self.sentence_sums, range))
+    }
+
+    /// Returns total size of paragraphes in `range` as [`Self::paragraphes_size`] does,
+    /// `None` if it does not fit `c_uint`
+    pub(crate) fn checked_paragraphes_size(&self, range: Range<ParagraphId>) -> Option<StringSize> {
+        checked(difference(&self.paragraph_sums, range))
+    }
+
+    /// Returns total size of sentences in `range` as [`Self::sentences_size`] does,
+    /// `None` if it does not fit `c_uint`
+    pub(crate) fn checked_sentences_size(&self, range: Range<SentenceId>) -> Option<StringSize> {
+        checked(difference(&self.sentence_sums, range))
+    }
+}
+
+/// Returns prefix sums of `sizes`, the first one is zero
+fn sums<I: Iterator<Item = StringSize>>(sizes: I) -> Vec<Sum> {
+    let mut sum = Sum::default();
+    let mut sums = vec![sum];
+    for size in sizes {
+        // Cannot overflow: there are at most `c_uint::MAX` sizes of `c_uint`
+        sum.bytes += u64::from(size.bytes);
+        sum.symbols += u64::from(size.symbols);
+        sums.push(sum);
+    }
+    sums
+}
+
+fn difference<I: Into<c_uint>>(sums: &[Sum], range: Range<I>) -> Sum {
+    let len = sums.len() - 1;
+    let end = (range.end.into() as usize).min(len);
+    let start = (range.start.into() as usize).min(end);
+
+    Sum {
+        bytes: sums[end].bytes - sums[start].bytes,
+        symbols: sums[end].symbols - sums[start].symbols,
+    }
+}
+
+fn checked(sum: Sum) -> Option<StringSize> {
+    Some(StringSize {
+        bytes: c_uint::try_from(sum.bytes).ok()?,
+        symbols: c_uint::try_from(sum.symbols).ok()?,
+    })
+}
+
+fn saturate(sum: Sum) -> StringSize {
+    StringSize {
+        bytes: c_uint::try_from(sum.bytes).unwrap_or(c_uint::MAX),
+        symbols: c_uint::try_from(sum.symbols).unwrap_or(c_uint::MAX),
+    }
+}
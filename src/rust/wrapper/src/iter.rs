@@ -0,0 +1,134 @@
+use std::{fmt::Debug, iter::FusedIterator, ops::Range, os::raw::c_uint, rc::Rc};
+
+use crate::{
+    ffi::{ParagraphId, SentenceId},
+    paragraph::Paragraph,
+    sentence::Sentence,
+    source::Source,
+};
+
+/// Iterator over a range of paragraphes, see [`crate::Book::paragraphes`]
+#[derive(Clone)]
+pub struct Paragraphes<'a> {
+    source: Rc<Source<'a>>,
+    range: Range<c_uint>,
+}
+
+impl<'a> Paragraphes<'a> {
+    /// Creates the iterator over `range` clamped to the paragraphes of the book
+    pub(crate) fn new(source: Rc<Source<'a>>, range: Range<ParagraphId>) -> Self {
+        let range = clamp(
+            range.start.into()..range.end.into(),
+            source.segmenter.book_info().paragraphes,
+        );
+        Self { source, range }
+    }
+}
+
+impl<'a> Iterator for Paragraphes<'a> {
+    type Item = Paragraph<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(Paragraph::new(Rc::clone(&self.source), index.into()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth(n)?;
+        Some(Paragraph::new(Rc::clone(&self.source), index.into()))
+    }
+}
+
+impl DoubleEndedIterator for Paragraphes<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(Paragraph::new(Rc::clone(&self.source), index.into()))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth_back(n)?;
+        Some(Paragraph::new(Rc::clone(&self.source), index.into()))
+    }
+}
+
+impl ExactSizeIterator for Paragraphes<'_> {}
+
+impl FusedIterator for Paragraphes<'_> {}
+
+impl Debug for Paragraphes<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Paragraphes")
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+/// Iterator over a range of sentences, see [`crate::Book::sentences`]
+#[derive(Clone)]
+pub struct Sentences<'a> {
+    source: Rc<Source<'a>>,
+    range: Range<c_uint>,
+}
+
+impl<'a> Sentences<'a> {
+    /// Creates the iterator over `range` clamped to the sentences of the book
+    pub(crate) fn new(source: Rc<Source<'a>>, range: Range<SentenceId>) -> Self {
+        let range = clamp(
+            range.start.into()..range.end.into(),
+            source.segmenter.book_info().sentences,
+        );
+        Self { source, range }
+    }
+}
+
+impl<'a> Iterator for Sentences<'a> {
+    type Item = Sentence<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        Some(Sentence::new(Rc::clone(&self.source), index.into()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth(n)?;
+        Some(Sentence::new(Rc::clone(&self.source), index.into()))
+    }
+}
+
+impl DoubleEndedIterator for Sentences<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        Some(Sentence::new(Rc::clone(&self.source), index.into()))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        let index = self.range.nth_back(n)?;
+        Some(Sentence::new(Rc::clone(&self.source), index.into()))
+    }
+}
+
+impl ExactSizeIterator for Sentences<'_> {}
+
+impl FusedIterator for Sentences<'_> {}
+
+impl Debug for Sentences<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sentences")
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+/// Limits `range` by `len` items, an inverted range becomes empty
+fn clamp(range: Range<c_uint>, len: c_uint) -> Range<c_uint> {
+    let end = range.end.min(len);
+    range.start.min(end)..end
+}
@@ -1,10 +1,54 @@
+#[cfg(feature = "tokio")]
+mod asynchronous;
 mod book;
+#[cfg(feature = "cache")]
+mod cache;
+mod chunked;
+mod error;
 mod ffi;
+mod index;
+mod iter;
+mod library;
+#[cfg(any(feature = "link-static", feature = "link-dynamic"))]
+mod linked;
+mod locate;
+mod mapped;
+mod memory;
+mod offsets;
+mod owned;
 mod paragraph;
+#[cfg(feature = "rust-segmenter")]
+mod rust_segmenter;
+mod segmenter;
 mod sentence;
+mod source;
+mod streaming;
+#[cfg(feature = "serde")]
+mod tree;
+mod verify;
 mod wrapper;
 
+#[cfg(feature = "tokio")]
+pub use asynchronous::{CancelToken, SentenceStream};
 pub use book::Book;
+#[cfg(feature = "cache")]
+pub use cache::Cache;
+pub use chunked::{Chunk, ChunkedBook, ChunkedParser, GlobalParagraph, GlobalSentence, Progress};
+pub use error::Error;
 pub use ffi::*;
+pub use index::BookIndex;
+pub use iter::{Paragraphes, Sentences};
+pub use library::{init, loaded_from};
+pub use locate::{candidates, library_filename, Candidate, LIBRARY_ENV};
+pub use memory::MemorySegmenter;
+pub use owned::OwnedBook;
 pub use paragraph::Paragraph;
+#[cfg(feature = "rust-segmenter")]
+pub use rust_segmenter::RustSegmenter;
+pub use segmenter::Segmenter;
 pub use sentence::Sentence;
+pub use streaming::{StreamedChunk, StreamingBook};
+#[cfg(feature = "serde")]
+pub use tree::{BookTree, ParagraphNode, SentenceNode};
+pub use verify::{Report, Violation};
+pub use wrapper::Wrapper;
@@ -0,0 +1,185 @@
+use libloading::Library;
+use std::{
+    os::raw::{c_uchar, c_uint},
+    path::Path,
+    sync::OnceLock,
+};
+
+use crate::{
+    error::Error,
+    ffi::*,
+    locate::{candidates, Candidate},
+};
+
+/// The native library shared by all books in the process
+static LIBRARY: OnceLock<BookLibrary> = OnceLock::new();
+
+/// Loads the native library that will be used by all books in the process.
+///
+/// Call it before the first book is parsed to control where the library is taken from,
+/// only `path` is tried. Otherwise the library is loaded from the default locations on
+/// first use, see [`candidates`].
+/// Returns [`Error::AlreadyInitialized`] if the library is already loaded or it is linked
+/// at build time (`link-static` and `link-dynamic` features).
+pub fn init<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    if cfg!(any(feature = "link-static", feature = "link-dynamic")) || LIBRARY.get().is_some() {
+        return Err(Error::AlreadyInitialized);
+    }
+
+    let explicit = Candidate::Explicit(path.as_ref().to_owned());
+    let lib = BookLibrary::new(vec![explicit])?;
+    LIBRARY.set(lib).map_err(|_| Error::AlreadyInitialized)
+}
+
+/// Returns where the process-wide library is loaded from.
+///
+/// Returns `None` if the library is not loaded yet or it is linked at build time.
+pub fn loaded_from() -> Option<Candidate> {
+    LIBRARY.get().and_then(|lib| lib.origin.clone())
+}
+
+/// Functions exported by the native library (see `lib/book_parse.h`)
+#[derive(Debug, Clone, Copy)]
+struct Functions {
+    from_utf8: unsafe extern "C" fn(*mut c_uchar, c_uint) -> BookRaw,
+    dispose: unsafe extern "C" fn(BookRaw),
+    book_info: unsafe extern "C" fn(BookRaw) -> BookInfo,
+    paragraph_info: unsafe extern "C" fn(BookRaw, ParagraphId) -> ParagraphInfo,
+    paragraph_text: unsafe extern "C" fn(BookRaw, ParagraphId, *mut c_uchar),
+    sentence_info: unsafe extern "C" fn(BookRaw, SentenceId) -> SentenceInfo,
+    sentence_text: unsafe extern "C" fn(BookRaw, SentenceId, *mut c_uchar),
+}
+
+impl Functions {
+    /// Resolves all symbols at once.
+    ///
+    /// # Safety
+    /// The resolved pointers are valid as long as `lib` is loaded.
+    unsafe fn resolve(lib: &Library) -> Result<Self, Error> {
+        unsafe fn get<T: Copy>(lib: &Library, name: &'static str) -> Result<T, Error> {
+            lib.get::<T>(name.as_bytes())
+                .map(|symbol| *symbol)
+                .map_err(|e| Error::MissingSymbol {
+                    name,
+                    reason: e.to_string(),
+                })
+        }
+
+        Ok(Self {
+            from_utf8: get(lib, "from_utf8")?,
+            dispose: get(lib, "dispose")?,
+            book_info: get(lib, "book_info")?,
+            paragraph_info: get(lib, "paragraph_info")?,
+            paragraph_text: get(lib, "paragraph_text")?,
+            sentence_info: get(lib, "sentence_info")?,
+            sentence_text: get(lib, "sentence_text")?,
+        })
+    }
+
+    #[cfg(any(feature = "link-static", feature = "link-dynamic"))]
+    fn linked() -> Self {
+        use crate::linked;
+
+        Self {
+            from_utf8: linked::from_utf8,
+            dispose: linked::dispose,
+            book_info: linked::book_info,
+            paragraph_info: linked::paragraph_info,
+            paragraph_text: linked::paragraph_text,
+            sentence_info: linked::sentence_info,
+            sentence_text: linked::sentence_text,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct BookLibrary {
+    functions: Functions,
+    /// Where the library is loaded from, `None` if it is linked at build time
+    origin: Option<Candidate>,
+    // Keeps `functions` valid, must be dropped last. `None` if the library is linked at build time.
+    _lib: Option<Library>,
+}
+
+impl BookLibrary {
+    /// Returns the process-wide library, it is loaded from the default locations on first call.
+    pub(crate) fn shared() -> Result<&'static Self, Error> {
+        #[cfg(any(feature = "link-static", feature = "link-dynamic"))]
+        return Ok(LIBRARY.get_or_init(|| Self {
+            functions: Functions::linked(),
+            origin: None,
+            _lib: None,
+        }));
+
+        #[allow(unreachable_code)]
+        if let Some(lib) = LIBRARY.get() {
+            return Ok(lib);
+        }
+
+        let lib = Self::new(candidates(None))?;
+        // Another thread may win the race, its library is used then and ours is unloaded.
+        Ok(LIBRARY.get_or_init(|| lib))
+    }
+
+    /// Loads the native library from the first of `candidates` that fits.
+    fn new(candidates: Vec<Candidate>) -> Result<Self, Error> {
+        let mut tried = vec![];
+
+        for candidate in candidates {
+            match Library::new(candidate.as_os_str()) {
+                Ok(lib) => return Self::with_library(lib, candidate),
+                Err(e) => tried.push((candidate, e.to_string())),
+            }
+        }
+
+        Err(Error::LibraryNotFound { tried })
+    }
+
+    fn with_library(lib: Library, origin: Candidate) -> Result<Self, Error> {
+        let functions = unsafe { Functions::resolve(&lib)? };
+        Ok(Self {
+            functions,
+            origin: Some(origin),
+            _lib: Some(lib),
+        })
+    }
+
+    pub(crate) fn parse_utf8(&self, pointer: *const c_uchar, len: c_uint) -> BookRaw {
+        // The text is not modified by the native library despite of `unsigned char *` in its header
+        unsafe { (self.functions.from_utf8)(pointer as *mut c_uchar, len) }
+    }
+
+    pub(crate) fn dispose(&self, book: BookRaw) {
+        unsafe { (self.functions.dispose)(book) }
+    }
+
+    pub(crate) fn book_info(&self, book: BookRaw) -> BookInfo {
+        unsafe { (self.functions.book_info)(book) }
+    }
+
+    pub(crate) fn paragraph_info(&self, book: BookRaw, index: ParagraphId) -> ParagraphInfo {
+        unsafe { (self.functions.paragraph_info)(book, index) }
+    }
+
+    pub(crate) unsafe fn paragraph_text(
+        &self,
+        book: BookRaw,
+        index: ParagraphId,
+        buff: *mut c_uchar,
+    ) {
+        (self.functions.paragraph_text)(book, index, buff)
+    }
+
+    pub(crate) fn sentence_info(&self, book: BookRaw, index: SentenceId) -> SentenceInfo {
+        unsafe { (self.functions.sentence_info)(book, index) }
+    }
+
+    pub(crate) unsafe fn sentence_text(
+        &self,
+        book: BookRaw,
+        index: SentenceId,
+        buff: *mut c_uchar,
+    ) {
+        (self.functions.sentence_text)(book, index, buff)
+    }
+}
@@ -0,0 +1,8 @@
+//! Declarations of the native functions linked at build time, generated by `build.rs`
+//! from `lib/book_parse.h`.
+
+use std::os::raw::{c_uchar, c_uint};
+
+use crate::ffi::*;
+
+include!(concat!(env!("OUT_DIR"), "/book_parse.rs"));
@@ -0,0 +1,93 @@
+use std::{
+    env::{
+        self,
+        consts::{DLL_PREFIX, DLL_SUFFIX},
+    },
+    ffi::{OsStr, OsString},
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable that may contain a path to the native library.
+pub const LIBRARY_ENV: &str = "BOOK_PARSE_LIB";
+
+/// Number of parents of the executable directory searched for the bundled library, it is
+/// enough for `src/rust/target/<profile>/deps` of this repository
+const BUNDLED_PARENTS: usize = 5;
+
+/// Name of the native library file for the current OS (`libbook_parse.so`, `book_parse.dll`, ...)
+pub fn library_filename() -> String {
+    format!("{}book_parse{}", DLL_PREFIX, DLL_SUFFIX)
+}
+
+/// A location where the native library may be found
+#[derive(Debug, Clone, PartialEq)]
+pub enum Candidate {
+    /// Explicit path passed by the caller
+    Explicit(PathBuf),
+    /// Path taken from `BOOK_PARSE_LIB` environment variable
+    Environment(PathBuf),
+    /// Path inside `lib/<target-triple>/` directory that is shipped with this repository
+    Bundled(PathBuf),
+    /// The file name only, resolved by the system loader (`PATH` \ `LD_LIBRARY_PATH` \ ...)
+    System(OsString),
+}
+
+impl Candidate {
+    pub(crate) fn as_os_str(&self) -> &OsStr {
+        match self {
+            Candidate::Explicit(path) | Candidate::Environment(path) | Candidate::Bundled(path) => {
+                path.as_os_str()
+            }
+            Candidate::System(name) => name.as_os_str(),
+        }
+    }
+}
+
+impl Display for Candidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Candidate::Explicit(path) => write!(f, "{} (explicit path)", path.display()),
+            Candidate::Environment(path) => {
+                write!(f, "{} (from {})", path.display(), LIBRARY_ENV)
+            }
+            Candidate::Bundled(path) => write!(f, "{} (bundled)", path.display()),
+            Candidate::System(name) => {
+                write!(f, "{} (system search path)", name.to_string_lossy())
+            }
+        }
+    }
+}
+
+/// Returns all locations of the native library in order they should be tried:
+/// explicit `path`, `BOOK_PARSE_LIB` environment variable, `lib/<target-triple>/`
+/// directory near the executable or up to five levels above it, the system search path.
+///
+/// The current directory is never searched, so a library planted there is not loaded.
+pub fn candidates(path: Option<&Path>) -> Vec<Candidate> {
+    let mut list = vec![];
+
+    if let Some(path) = path {
+        list.push(Candidate::Explicit(path.to_owned()));
+    }
+
+    if let Some(path) = env::var_os(LIBRARY_ENV).filter(|p| !p.is_empty()) {
+        list.push(Candidate::Environment(PathBuf::from(path)));
+    }
+
+    let bundled = Path::new("lib")
+        .join(env!("BOOK_PARSE_TARGET"))
+        .join(library_filename());
+
+    if let Some(base) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_owned))
+    {
+        for dir in base.ancestors().take(BUNDLED_PARENTS + 1) {
+            list.push(Candidate::Bundled(dir.join(&bundled)));
+        }
+    }
+
+    list.push(Candidate::System(library_filename().into()));
+    list
+}
@@ -0,0 +1,55 @@
+//! Books parsed right from memory-mapped files.
+
+use std::{fs::File, io, path::Path, rc::Rc};
+
+use memmap2::Mmap;
+
+use crate::{
+    book::Book,
+    error::Error,
+    segmenter::Segmenter,
+    source::{Source, Text},
+    wrapper::Wrapper,
+};
+
+impl Book<'static> {
+    /// Memory-maps the file at `path` and parses it with the native library, the text is
+    /// not copied. The book owns the mapping.
+    ///
+    /// The whole file is checked to be UTF-8 before parsing rather than lazily: the native
+    /// library reads all of it at once and requires valid UTF-8 anyway. Returns
+    /// [`Error::Io`] of [`io::ErrorKind::InvalidData`] kind if the file is not UTF-8.
+    ///
+    /// The file must not be truncated or rewritten while the book is alive, by this or
+    /// another process. The mapping would change under the book then, and its text, that
+    /// is not checked again, may become invalid UTF-8 or fault on access. Use
+    /// [`Book::from_utf8`] over a read text if the file may change.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let (wrapper, text) = parse(path.as_ref())?;
+        Ok(Book::from_mapped(wrapper, text))
+    }
+
+    /// Creates a book over the `wrapper` parsed from the mapped `text`
+    pub(crate) fn from_mapped(wrapper: Wrapper, text: Text<'static>) -> Self {
+        Book::from_source(Rc::new(Source::new(Box::new(wrapper), text)))
+    }
+}
+
+/// Memory-maps the file at `path` and parses it, both are `Send` unlike the book
+pub(crate) fn parse(path: &Path) -> Result<(Wrapper, Text<'static>), Error> {
+    let file = File::open(path)?;
+    if file.metadata()?.len() == 0 {
+        return Err(Error::EmptyInput);
+    }
+
+    // The file must not be changed while it is mapped, see `Book::from_path`. It cannot
+    // be enforced, so the function is safe as mapping files in Rust usually is.
+    let map = unsafe { Mmap::map(&file)? };
+    let text = Text::mapped(map).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let wrapper = Wrapper::from_utf8(&text)?;
+    if wrapper.book_info().size.bytes == 0 {
+        return Err(Error::EmptyInput);
+    }
+
+    Ok((wrapper, text))
+}
@@ -0,0 +1,122 @@
+use std::os::raw::c_uint;
+
+use crate::{error::Error, ffi::*, segmenter::Segmenter};
+
+/// [`Segmenter`] over data that is already held in memory.
+///
+/// Paragraphes and sentences must be ordered by their indexes.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemorySegmenter {
+    info: BookInfo,
+    paragraphes: Vec<(ParagraphInfo, String)>,
+    sentences: Vec<(SentenceInfo, String)>,
+}
+
+impl MemorySegmenter {
+    pub fn new(
+        info: BookInfo,
+        paragraphes: Vec<(ParagraphInfo, String)>,
+        sentences: Vec<(SentenceInfo, String)>,
+    ) -> Self {
+        Self {
+            info,
+            paragraphes,
+            sentences,
+        }
+    }
+
+    /// Pulls all infos and texts from `segmenter`
+    pub fn from_segmenter<S: Segmenter + ?Sized>(segmenter: &S) -> Result<Self, Error> {
+        let info = segmenter.book_info();
+
+        let paragraphes = (0..info.paragraphes)
+            .map(|i| {
+                let index = ParagraphId::from(i);
+                Ok((
+                    segmenter.paragraph_info(index),
+                    segmenter.paragraph_text(index)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let sentences = (0..info.sentences)
+            .map(|i| {
+                let index = SentenceId::from(i);
+                Ok((
+                    segmenter.sentence_info(index),
+                    segmenter.sentence_text(index)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(Self::new(info, paragraphes, sentences))
+    }
+
+    fn paragraph(&self, index: ParagraphId) -> Result<&str, Error> {
+        match self.paragraphes.get(c_uint::from(index) as usize) {
+            Some((_, text)) => Ok(text),
+            None => Err(Error::ParagraphOutOfRange {
+                index,
+                len: self.info.paragraphes,
+            }),
+        }
+    }
+
+    fn sentence(&self, index: SentenceId) -> Result<&str, Error> {
+        match self.sentences.get(c_uint::from(index) as usize) {
+            Some((_, text)) => Ok(text),
+            None => Err(Error::SentenceOutOfRange {
+                index,
+                len: self.info.sentences,
+            }),
+        }
+    }
+
+    /// Returns infos and texts of all paragraphes
+    pub fn paragraphes(&self) -> &[(ParagraphInfo, String)] {
+        &self.paragraphes
+    }
+
+    /// Returns infos and texts of all sentences
+    pub fn sentences(&self) -> &[(SentenceInfo, String)] {
+        &self.sentences
+    }
+}
+
+impl Segmenter for MemorySegmenter {
+    fn book_info(&self) -> BookInfo {
+        self.info
+    }
+
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        self.paragraphes
+            .get(c_uint::from(index) as usize)
+            .map(|p| p.0)
+            .unwrap_or_default()
+    }
+
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error> {
+        self.paragraph(index).map(String::from)
+    }
+
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        self.sentences
+            .get(c_uint::from(index) as usize)
+            .map(|s| s.0)
+            .unwrap_or_default()
+    }
+
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error> {
+        self.sentence(index).map(String::from)
+    }
+
+    fn write_paragraph_text(&self, index: ParagraphId, buf: &mut String) -> Result<(), Error> {
+        buf.push_str(self.paragraph(index)?);
+        Ok(())
+    }
+
+    fn write_sentence_text(&self, index: SentenceId, buf: &mut String) -> Result<(), Error> {
+        buf.push_str(self.sentence(index)?);
+        Ok(())
+    }
+}
@@ -0,0 +1,105 @@
+//! Lookup of paragraphes and sentences by offsets in the original text, see
+//! [`crate::Book::sentence_at_byte`].
+
+use std::{ops::Range, os::raw::c_uint};
+
+use crate::source::Source;
+
+/// A paragraph or a sentence found in the original text
+#[derive(Debug, Clone)]
+struct Located {
+    index: c_uint,
+    bytes: Range<usize>,
+    chars: Range<usize>,
+}
+
+/// Byte and char ranges of paragraphes and sentences in the original text, in their
+/// order. Those that cannot be found there are skipped.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Offsets {
+    paragraphes: Vec<Located>,
+    sentences: Vec<Located>,
+}
+
+impl Offsets {
+    pub fn new(source: &Source) -> Self {
+        let text = &*source.text;
+
+        // Spans follow each other, so chars are counted once over the text
+        let (mut byte, mut char) = (0, 0);
+        let mut chars_at = |offset: usize| {
+            char += text[byte..offset].chars().count();
+            byte = offset;
+            char
+        };
+        let sentences = source
+            .spans()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, span)| {
+                let span = span.as_ref()?;
+                let chars = chars_at(span.start)..chars_at(span.end);
+                Some(Located {
+                    index: i as c_uint,
+                    bytes: span.clone(),
+                    chars,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        // A paragraph spans from its first sentence to its last one, as `Paragraph::span`
+        let located = |index: c_uint| {
+            let i = sentences.binary_search_by_key(&index, |s| s.index).ok()?;
+            Some(&sentences[i])
+        };
+        let paragraphes = (0..source.segmenter.book_info().paragraphes)
+            .filter_map(|i| {
+                let info = source.paragraph_info(i.into());
+                let first = c_uint::from(info.sentence_first);
+                let last = first.checked_add(info.sentences)?.checked_sub(1)?;
+                let (first, last) = (located(first)?, located(last)?);
+                Some(Located {
+                    index: i,
+                    bytes: first.bytes.start..last.bytes.end,
+                    chars: first.chars.start..last.chars.end,
+                })
+            })
+            .collect();
+
+        Self {
+            paragraphes,
+            sentences,
+        }
+    }
+
+    pub fn paragraph_at_byte(&self, offset: usize) -> Option<c_uint> {
+        find(&self.paragraphes, offset, |p| &p.bytes)
+    }
+
+    pub fn paragraph_at_char(&self, offset: usize) -> Option<c_uint> {
+        find(&self.paragraphes, offset, |p| &p.chars)
+    }
+
+    pub fn sentence_at_byte(&self, offset: usize) -> Option<c_uint> {
+        find(&self.sentences, offset, |s| &s.bytes)
+    }
+
+    pub fn sentence_at_char(&self, offset: usize) -> Option<c_uint> {
+        find(&self.sentences, offset, |s| &s.chars)
+    }
+}
+
+/// Returns index of the item whose `range` contains `offset`, `None` if it is in a gap
+/// between items or out of them
+fn find<F: Fn(&Located) -> &Range<usize>>(
+    located: &[Located],
+    offset: usize,
+    range: F,
+) -> Option<c_uint> {
+    let after = located.partition_point(|l| range(l).start <= offset);
+    let item = &located[after.checked_sub(1)?];
+    match range(item).contains(&offset) {
+        true => Some(item.index),
+        false => None,
+    }
+}
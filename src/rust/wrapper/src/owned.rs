@@ -0,0 +1,97 @@
+use std::{ops::Range, rc::Rc, sync::Arc};
+
+use crate::{
+    book::Book,
+    ffi::{BookInfo, ParagraphId, SentenceId},
+    iter::{Paragraphes, Sentences},
+    memory::MemorySegmenter,
+    paragraph::Paragraph,
+    segmenter::Segmenter,
+    sentence::Sentence,
+    source::{Source, Spans},
+};
+
+/// Fully materialised book that does not depend on the native library.
+///
+/// It is cheap to clone and can be shared across threads. Paragraphes and sentences
+/// are available with the same API as [`Book`] provides, see [`Self::book`].
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "crate::tree::BookTree", from = "crate::tree::BookTree")
+)]
+pub struct OwnedBook {
+    data: Arc<MemorySegmenter>,
+    text: Arc<str>,
+    spans: Spans,
+}
+
+impl OwnedBook {
+    pub(crate) fn new(data: MemorySegmenter, text: Arc<str>, spans: Spans) -> Self {
+        Self {
+            data: Arc::new(data),
+            text,
+            spans,
+        }
+    }
+
+    /// Returns a view of the book to navigate over its paragraphes and sentences
+    pub fn book(&self) -> Book<'_> {
+        let segmenter = Box::new(Arc::clone(&self.data));
+        let source = Source::with_spans(segmenter, &self.text, Arc::clone(&self.spans));
+        Book::from_source(Rc::new(source))
+    }
+
+    /// Returns the in-memory data of the book
+    pub fn segmenter(&self) -> &MemorySegmenter {
+        &self.data
+    }
+
+    pub fn info(&self) -> BookInfo {
+        self.data.book_info()
+    }
+
+    /// Returns the original text of the book
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns count of paragraphes in the book
+    pub fn paragraphes_len(&self) -> usize {
+        self.info().paragraphes as usize
+    }
+
+    /// Returns count of sentences in the book
+    pub fn sentences_len(&self) -> usize {
+        self.info().sentences as usize
+    }
+
+    /// Returns the paragraph by its unique `index` or `None` if it is out of range
+    pub fn paragraph(&self, index: ParagraphId) -> Option<Paragraph<'_>> {
+        self.book().paragraph(index)
+    }
+
+    /// Returns the sentence by its unique `index` or `None` if it is out of range
+    pub fn sentence(&self, index: SentenceId) -> Option<Sentence<'_>> {
+        self.book().sentence(index)
+    }
+
+    pub fn paragraphes(&self) -> Paragraphes<'_> {
+        self.book().paragraphes()
+    }
+
+    pub fn sentences(&self) -> Sentences<'_> {
+        self.book().sentences()
+    }
+
+    /// Returns paragraphes with indexes in `range`, see [`Book::paragraphes_in`]
+    pub fn paragraphes_in(&self, range: Range<ParagraphId>) -> Paragraphes<'_> {
+        self.book().paragraphes_in(range)
+    }
+
+    /// Returns sentences with indexes in `range`, see [`Book::sentences_in`]
+    pub fn sentences_in(&self, range: Range<SentenceId>) -> Sentences<'_> {
+        self.book().sentences_in(range)
+    }
+}
@@ -1,40 +1,111 @@
-use std::{fmt::Debug, os::raw::c_uint, sync::Arc};
+use std::{fmt::Debug, ops::Range, os::raw::c_uint, rc::Rc};
 
-use crate::sentence::Sentence;
 use crate::{
+    error::Error,
     ffi::{ParagraphId, ParagraphInfo},
-    wrapper::Wrapper,
+    iter::Sentences,
+    sentence::Sentence,
+    source::Source,
 };
 
-pub struct Paragraph {
+pub struct Paragraph<'a> {
     index: ParagraphId,
-    ffi: Arc<Wrapper>,
+    source: Rc<Source<'a>>,
     info: ParagraphInfo,
 }
 
-impl Paragraph {
-    pub(super) fn new(ffi: Arc<Wrapper>, index: ParagraphId) -> Self {
-        let info = ffi.paragraph_info(index);
-        Self { index, ffi, info }
+impl<'a> Paragraph<'a> {
+    pub(super) fn new(source: Rc<Source<'a>>, index: ParagraphId) -> Self {
+        let info = source.paragraph_info(index);
+        Self {
+            index,
+            source,
+            info,
+        }
     }
 
     pub fn info(&self) -> ParagraphInfo {
         self.info
     }
 
-    pub fn text(&self) -> Option<String> {
-        self.ffi.paragraph_text(self.index)
+    pub fn text(&self) -> Result<String, Error> {
+        self.source.segmenter.paragraph_text(self.index)
     }
 
-    pub fn sentences(&self) -> impl Iterator<Item = Sentence> + '_ {
-        let paragraph_info = self.info();
-        let first_index: c_uint = paragraph_info.sentence_first.into();
-        let range = first_index..(paragraph_info.sentences + first_index);
-        range.map(move |i| Sentence::new(Arc::clone(&self.ffi), i.into()))
+    /// Appends the text to `buf` without a temporary allocation, `buf` is left unchanged
+    /// on error. Reuse `buf` to read many texts.
+    pub fn write_text_into(&self, buf: &mut String) -> Result<(), Error> {
+        self.source.segmenter.write_paragraph_text(self.index, buf)
+    }
+
+    /// Returns byte range of the paragraph in the original text, from the start of its
+    /// first sentence to the end of its last one. Returns `None` if they cannot be found.
+    pub fn span(&self) -> Option<Range<usize>> {
+        let first = c_uint::from(self.info.sentence_first);
+        let last = first.checked_add(self.info.sentences)?.checked_sub(1)?;
+        let start = self.source.sentence_span(first.into())?.start;
+        let end = self.source.sentence_span(last.into())?.end;
+        Some(start..end)
+    }
+
+    /// Returns the paragraph as a slice of the original text without copying, see [`Self::span`].
+    /// Unlike [`Self::text`], whitespaces between sentences are kept as is.
+    pub fn text_ref(&self) -> Option<&str> {
+        self.span().map(|span| &self.source.text[span])
+    }
+
+    /// Returns the original text between the previous paragraph (or the start of the book)
+    /// and this one, it contains line breaks and blank lines between them.
+    /// Returns `None` if the paragraph is not found in the original text, see [`Self::span`].
+    pub fn gap_before(&self) -> Option<&str> {
+        let gap = self
+            .source
+            .gap_before(self.first_sentence()?.info().index)?;
+        Some(&self.source.text[gap])
+    }
+
+    /// Returns the original text between this paragraph and the next one (or the end of
+    /// the book), see [`Self::gap_before`]
+    pub fn gap_after(&self) -> Option<&str> {
+        let gap = self.source.gap_after(self.last_sentence()?.info().index)?;
+        Some(&self.source.text[gap])
+    }
+
+    /// Returns the next paragraph in the book or `None` if this one is the last
+    pub fn next(&self) -> Option<Paragraph<'a>> {
+        let index = c_uint::from(self.index).checked_add(1)?;
+        self.source.paragraph(index.into())
+    }
+
+    /// Returns the previous paragraph in the book or `None` if this one is the first
+    pub fn prev(&self) -> Option<Paragraph<'a>> {
+        let index = c_uint::from(self.index).checked_sub(1)?;
+        self.source.paragraph(index.into())
+    }
+
+    /// Returns the first sentence of the paragraph or `None` if it has no sentences
+    pub fn first_sentence(&self) -> Option<Sentence<'a>> {
+        match self.info.sentences {
+            0 => None,
+            _ => self.source.sentence(self.info.sentence_first),
+        }
+    }
+
+    /// Returns the last sentence of the paragraph or `None` if it has no sentences
+    pub fn last_sentence(&self) -> Option<Sentence<'a>> {
+        let first = c_uint::from(self.info.sentence_first);
+        let last = first.checked_add(self.info.sentences)?.checked_sub(1)?;
+        self.source.sentence(last.into())
+    }
+
+    pub fn sentences(&self) -> Sentences<'a> {
+        let first = self.info.sentence_first;
+        let last = c_uint::from(first).saturating_add(self.info.sentences);
+        Sentences::new(Rc::clone(&self.source), first..last.into())
     }
 }
 
-impl Debug for Paragraph {
+impl Debug for Paragraph<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Paragraph")
             .field("index", &self.index)
@@ -0,0 +1,288 @@
+//! Pure Rust segmentation that follows the rules of the native `book_parse` library
+//! for ordinary prose. It does not require the native library at all.
+//!
+//! Known differences, they are left out of the conformance corpus:
+//!
+//! * runs of mixed terminators (`Wait.. what`, `Hey?.. what`) are split by the native
+//!   library after the first char and kept whole here;
+//! * a parenthetical in quotes at the start of a sentence (`"(Note.)" Next.`) does not
+//!   end the sentence here;
+//! * the native library repeats sentences in some paragraphes where `...` or `!` both
+//!   ends a sentence and does not (`Hey! you there. Hey! You there.`), and it fails on
+//!   a text that ends with `…`.
+
+use std::{convert::TryFrom, os::raw::c_uint};
+
+use crate::{error::Error, ffi::*, memory::MemorySegmenter, segmenter::Segmenter};
+
+/// Abbreviations that never end a sentence
+const ABBREVIATIONS: &[&str] = &[
+    "capt", "col", "dr", "fig", "gen", "lt", "mr", "mrs", "ms", "mt", "prof", "rev", "sgt", "st",
+    "vs",
+];
+
+/// Abbreviations that end a sentence only if the next word is capitalized
+const ABBREVIATIONS_UPPER: &[&str] = &["a.m", "etc", "p.m"];
+
+/// [`Segmenter`] that parses the text in Rust
+#[derive(Debug)]
+pub struct RustSegmenter(MemorySegmenter);
+
+impl RustSegmenter {
+    pub fn from_utf8(text: &str) -> Result<Self, Error> {
+        if text.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+        c_uint::try_from(text.len()).map_err(|_| Error::TextTooLarge { len: text.len() })?;
+
+        let mut paragraphes = vec![];
+        let mut sentences = vec![];
+        let mut size: Option<StringSize> = None;
+
+        for line in text.split('\n') {
+            let line = normalize(line);
+            if line.is_empty() {
+                continue;
+            }
+
+            let index = ParagraphId::from(paragraphes.len() as c_uint);
+            let sentence_first = SentenceId::from(sentences.len() as c_uint);
+            // `…` does not end a sentence, but it is written as `...`
+            let parts = split_sentences(&line)
+                .into_iter()
+                .map(|part| part.replace('…', "..."))
+                .collect::<Vec<_>>();
+
+            for (s_number, part) in parts.iter().enumerate() {
+                let info = SentenceInfo {
+                    index: SentenceId::from(sentences.len() as c_uint),
+                    s_number: s_number as c_uint,
+                    p_index: index,
+                    size: StringSize::from(part.as_str()),
+                };
+                sentences.push((info, part.clone()));
+            }
+
+            let text = parts.join(" ");
+            let info = ParagraphInfo {
+                index,
+                sentence_first,
+                sentences: parts.len() as c_uint,
+                size: StringSize::from(text.as_str()),
+            };
+
+            // Paragraphes are separated by a single line break
+            size = Some(match size {
+                Some(size) => size + StringSize::from("\n") + info.size,
+                None => info.size,
+            });
+
+            paragraphes.push((info, text));
+        }
+
+        let info = BookInfo {
+            paragraphes: paragraphes.len() as c_uint,
+            sentences: sentences.len() as c_uint,
+            size: size.unwrap_or_default(),
+        };
+
+        Ok(Self(MemorySegmenter::new(info, paragraphes, sentences)))
+    }
+}
+
+impl Segmenter for RustSegmenter {
+    fn book_info(&self) -> BookInfo {
+        self.0.book_info()
+    }
+
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        self.0.paragraph_info(index)
+    }
+
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error> {
+        self.0.paragraph_text(index)
+    }
+
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        self.0.sentence_info(index)
+    }
+
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error> {
+        self.0.sentence_text(index)
+    }
+
+    fn write_paragraph_text(&self, index: ParagraphId, buf: &mut String) -> Result<(), Error> {
+        self.0.write_paragraph_text(index, buf)
+    }
+
+    fn write_sentence_text(&self, index: SentenceId, buf: &mut String) -> Result<(), Error> {
+        self.0.write_sentence_text(index, buf)
+    }
+}
+
+/// Trims the line and replaces every run of several whitespaces by a single space
+fn normalize(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() && chars.peek().is_some_and(|c| c.is_whitespace()) {
+            while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                chars.next();
+            }
+            out.push(' ');
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?')
+}
+
+fn is_closing_quote(c: char) -> bool {
+    matches!(c, '"' | '\'' | '»' | '”' | '’')
+}
+
+fn is_dash(c: char) -> bool {
+    matches!(c, '-' | '–' | '—')
+}
+
+/// Splits a normalized line into sentences
+fn split_sentences(line: &str) -> Vec<&str> {
+    let chars = line.char_indices().collect::<Vec<_>>();
+    let offset = |i: usize| chars.get(i).map_or(line.len(), |c| c.0);
+
+    let mut sentences = vec![];
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        // A sentence that starts with a parenthetical may end right after it
+        if offset(i) == start && chars[i].1 == '(' {
+            if let Some(close) = closing_parenthesis(&chars[i..]) {
+                i += close + 1;
+                while i < chars.len() && is_closing_quote(chars[i].1) {
+                    i += 1;
+                }
+                let next = chars.get(i + 1).map(|c| c.1);
+                if chars.get(i).is_some_and(|c| c.1.is_whitespace())
+                    && next.is_some_and(char::is_uppercase)
+                {
+                    sentences.push(&line[start..offset(i)]);
+                    start = offset(i + 1);
+                }
+                continue;
+            }
+        }
+
+        if !is_terminator(chars[i].1) {
+            i += 1;
+            continue;
+        }
+
+        let terminator = i;
+        while i < chars.len() && is_terminator(chars[i].1) {
+            i += 1;
+        }
+        while i < chars.len() && is_closing_quote(chars[i].1) {
+            i += 1;
+        }
+
+        if i == chars.len() || !chars[i].1.is_whitespace() {
+            continue;
+        }
+
+        // The line is normalized, so a single whitespace is followed by a word
+        let next = chars[i + 1].1;
+        let before = &line[start..offset(terminator)];
+        let word = before
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+
+        // A number that starts a line is an item of a numbered list
+        let numbered = word == before && word.chars().all(|c| c.is_ascii_digit());
+
+        if !numbered && is_boundary(word, &line[offset(terminator)..offset(i)], next) {
+            sentences.push(&line[start..offset(i)]);
+            start = offset(i + 1);
+        }
+    }
+
+    if start < line.len() {
+        sentences.push(&line[start..]);
+    }
+
+    sentences
+}
+
+/// Returns position of the parenthesis that closes the one at the start of `chars`
+fn closing_parenthesis(chars: &[(usize, char)]) -> Option<usize> {
+    let mut depth = 0_usize;
+    for (i, &(_, c)) in chars.iter().enumerate() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+/// Checks whether `word` followed by `terminator` (with closing quotes) ends a sentence
+/// if `next` char follows it
+fn is_boundary(word: &str, terminator: &str, next: char) -> bool {
+    if is_dash(next) {
+        return false;
+    }
+
+    // A quoted phrase followed by its attribution: "Where are you going?" she asked.
+    if terminator.ends_with(is_closing_quote) && next.is_lowercase() {
+        return false;
+    }
+
+    match terminator {
+        "." => (),
+        // An exclamation within a sentence: Hey! you there.
+        "!" => return !next.is_lowercase(),
+        // A pause within a sentence: Wait... what?
+        "..." => return next.is_uppercase(),
+        _ => return true,
+    }
+
+    let word = word.trim_start_matches(|c: char| !c.is_alphanumeric());
+    let lowercase = word.to_lowercase();
+    if ABBREVIATIONS.contains(&lowercase.as_str()) {
+        return false;
+    }
+    if ABBREVIATIONS_UPPER.contains(&lowercase.as_str()) {
+        return next.is_uppercase();
+    }
+
+    let mut letters = word.split('.').map(|part| {
+        let mut chars = part.chars();
+        chars.next().filter(|_| chars.next().is_none())
+    });
+
+    // Initials and abbreviations of single letters: J. R. R. Tolkien, e.g., U.S.A.
+    if word.contains('.') && letters.all(|letter| letter.is_some_and(char::is_alphabetic)) {
+        return false;
+    }
+    // A single capital is an initial, a single small letter is an item: Part b. Next.
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_alphabetic() => {
+            letter.is_lowercase() && next.is_uppercase()
+        }
+        _ => true,
+    }
+}
@@ -0,0 +1,71 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{error::Error, ffi::*};
+
+/// Source of the parsed data used by `Book`, `Paragraph` and `Sentence`.
+///
+/// [`crate::Wrapper`] implements it over the native library, other implementations
+/// may provide alternative backends, fakes for tests or decorators of another segmenter.
+/// A segmenter must be `Send`, so a parsed segmenter may be moved to another thread.
+pub trait Segmenter: Debug + Send {
+    /// Returns basic info of the parsed text
+    fn book_info(&self) -> BookInfo;
+
+    /// Returns basic info of the paragraph by its unique `index`, the default info if
+    /// there is no such paragraph
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo;
+
+    /// Returns a text of the paragraph by its unique `index`
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error>;
+
+    /// Returns basic info of the sentence by its unique `index`, the default info if
+    /// there is no such sentence
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo;
+
+    /// Returns a text of the sentence by its unique `index`
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error>;
+
+    /// Appends a text of the paragraph to `buf`, it is left unchanged on error.
+    /// Implementations should override it to avoid the temporary `String`.
+    fn write_paragraph_text(&self, index: ParagraphId, buf: &mut String) -> Result<(), Error> {
+        buf.push_str(&self.paragraph_text(index)?);
+        Ok(())
+    }
+
+    /// Appends a text of the sentence to `buf`, it is left unchanged on error.
+    /// Implementations should override it to avoid the temporary `String`.
+    fn write_sentence_text(&self, index: SentenceId, buf: &mut String) -> Result<(), Error> {
+        buf.push_str(&self.sentence_text(index)?);
+        Ok(())
+    }
+}
+
+impl<S: Segmenter + Sync + ?Sized> Segmenter for Arc<S> {
+    fn book_info(&self) -> BookInfo {
+        (**self).book_info()
+    }
+
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        (**self).paragraph_info(index)
+    }
+
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error> {
+        (**self).paragraph_text(index)
+    }
+
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        (**self).sentence_info(index)
+    }
+
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error> {
+        (**self).sentence_text(index)
+    }
+
+    fn write_paragraph_text(&self, index: ParagraphId, buf: &mut String) -> Result<(), Error> {
+        (**self).write_paragraph_text(index, buf)
+    }
+
+    fn write_sentence_text(&self, index: SentenceId, buf: &mut String) -> Result<(), Error> {
+        (**self).write_sentence_text(index, buf)
+    }
+}
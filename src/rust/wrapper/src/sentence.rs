@@ -1,28 +1,97 @@
-use std::{fmt::Debug, os::raw::c_uint, sync::Arc};
+use std::{convert::TryFrom, fmt::Debug, ops::Range, os::raw::c_uint, rc::Rc};
 
 use crate::{
+    error::Error,
     ffi::{SentenceId, SentenceInfo},
-    wrapper::Wrapper,
+    iter::Sentences,
+    paragraph::Paragraph,
+    source::Source,
 };
 
-pub struct Sentence {
+pub struct Sentence<'a> {
     index: SentenceId,
-    ffi: Arc<Wrapper>,
+    source: Rc<Source<'a>>,
     info: SentenceInfo,
 }
 
-impl Sentence {
-    pub(super) fn new(ffi: Arc<Wrapper>, index: SentenceId) -> Self {
-        let info = ffi.sentence_info(index);
-        Self { index, ffi, info }
+impl<'a> Sentence<'a> {
+    pub(super) fn new(source: Rc<Source<'a>>, index: SentenceId) -> Self {
+        let info = source.sentence_info(index);
+        Self {
+            index,
+            source,
+            info,
+        }
     }
 
     pub fn info(&self) -> SentenceInfo {
         self.info
     }
 
-    pub fn text(&self) -> Option<String> {
-        self.ffi.sentence_text(self.index)
+    pub fn text(&self) -> Result<String, Error> {
+        self.source.segmenter.sentence_text(self.index)
+    }
+
+    /// Appends the text to `buf` without a temporary allocation, `buf` is left unchanged
+    /// on error. Reuse `buf` to read many texts.
+    pub fn write_text_into(&self, buf: &mut String) -> Result<(), Error> {
+        self.source.segmenter.write_sentence_text(self.index, buf)
+    }
+
+    /// Returns byte range of the sentence in the original text or `None` if the sentence
+    /// cannot be found there. Spans of all sentences are located on first call.
+    pub fn span(&self) -> Option<Range<usize>> {
+        self.source.sentence_span(self.index)
+    }
+
+    /// Returns the sentence as a slice of the original text without copying, see [`Self::span`].
+    /// It may differ from [`Self::text`] by whitespaces that the segmenter normalizes.
+    pub fn text_ref(&self) -> Option<&str> {
+        self.span().map(|span| &self.source.text[span])
+    }
+
+    /// Returns the original text between the previous sentence (or the start of the book)
+    /// and this one: whitespaces, line breaks and anything the segmenter skipped.
+    /// Returns `None` if the sentence is not found in the original text, see [`Self::span`].
+    pub fn gap_before(&self) -> Option<&str> {
+        let gap = self.source.gap_before(self.index)?;
+        Some(&self.source.text[gap])
+    }
+
+    /// Returns the original text between this sentence and the next one (or the end of
+    /// the book), see [`Self::gap_before`]
+    pub fn gap_after(&self) -> Option<&str> {
+        let gap = self.source.gap_after(self.index)?;
+        Some(&self.source.text[gap])
+    }
+
+    /// Returns the paragraph that contains the sentence
+    pub fn paragraph(&self) -> Option<Paragraph<'a>> {
+        self.source.paragraph(self.info.p_index)
+    }
+
+    /// Returns the next sentence in the book, it may be in the next paragraph
+    pub fn next(&self) -> Option<Sentence<'a>> {
+        let index = c_uint::from(self.index).checked_add(1)?;
+        self.source.sentence(index.into())
+    }
+
+    /// Returns the previous sentence in the book, it may be in the previous paragraph
+    pub fn prev(&self) -> Option<Sentence<'a>> {
+        let index = c_uint::from(self.index).checked_sub(1)?;
+        self.source.sentence(index.into())
+    }
+
+    /// Returns up to `n` sentences before this one, the sentence itself and up to `n`
+    /// sentences after it, in the book order and regardless of paragraphes.
+    pub fn context(&self, n: usize) -> Sentences<'a> {
+        let n = c_uint::try_from(n).unwrap_or(c_uint::MAX);
+        let index = c_uint::from(self.index);
+        let range = index.saturating_sub(n)..index.saturating_add(n).saturating_add(1);
+        Sentences::new(
+            Rc::clone(&self.source),
+            range.start.into()..range.end.into(),
+        )
     }
 
     /// Returns `true` if the sentence has the first at its paragraph
@@ -32,13 +101,12 @@ impl Sentence {
 
     /// Returns `true` if the sentence has the last position its paragraph
     pub fn is_last(&self) -> bool {
-        let si = self.ffi.sentence_info(self.index);
-        let pi = self.ffi.paragraph_info(si.p_index);
-        c_uint::from(pi.sentence_first) + si.s_number == self.index.into()
+        let pi = self.source.paragraph_info(self.info.p_index);
+        self.info.s_number + 1 == pi.sentences
     }
 }
 
-impl Debug for Sentence {
+impl Debug for Sentence<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Paragraph")
             .field("index", &self.index)
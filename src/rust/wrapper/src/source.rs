@@ -0,0 +1,220 @@
+use std::{
+    ops::{Deref, Range},
+    os::raw::c_uint,
+    rc::Rc,
+    str::Utf8Error,
+    sync::{Arc, OnceLock},
+};
+
+use memmap2::Mmap;
+
+use crate::{
+    ffi::{ParagraphId, ParagraphInfo, SentenceId, SentenceInfo},
+    index::BookIndex,
+    offsets::Offsets,
+    paragraph::Paragraph,
+    segmenter::Segmenter,
+    sentence::Sentence,
+};
+
+/// Byte ranges of sentences in the original text, `None` if a sentence is not found there
+pub(crate) type Spans = Arc<[Option<Range<usize>>]>;
+
+/// The original text of a book, borrowed or owned by the book
+#[derive(Debug)]
+pub(crate) enum Text<'a> {
+    Borrowed(&'a str),
+    /// Memory-mapped file, it is checked to be UTF-8 by [`Text::mapped`]
+    Mapped(Mmap),
+}
+
+impl Text<'static> {
+    pub fn mapped(map: Mmap) -> Result<Self, Utf8Error> {
+        std::str::from_utf8(&map)?;
+        Ok(Text::Mapped(map))
+    }
+}
+
+impl Deref for Text<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            Text::Borrowed(text) => text,
+            // Checked on creation, the file must not be changed, see `Book::from_path`
+            Text::Mapped(map) => unsafe { std::str::from_utf8_unchecked(map) },
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Text<'a> {
+    fn from(text: &'a str) -> Self {
+        Text::Borrowed(text)
+    }
+}
+
+/// Parsed data and the original text shared by a book and its paragraphes and sentences
+#[derive(Debug)]
+pub(crate) struct Source<'a> {
+    pub segmenter: Box<dyn Segmenter>,
+    pub text: Text<'a>,
+    /// Byte ranges of sentences in `text`, built on first use
+    spans: OnceLock<Spans>,
+    /// Infos and sizes of paragraphes and sentences, built on demand
+    index: OnceLock<BookIndex>,
+    /// Ranges of paragraphes and sentences in `text`, built on demand
+    offsets: OnceLock<Offsets>,
+}
+
+impl<'a> Source<'a> {
+    pub fn new<T: Into<Text<'a>>>(segmenter: Box<dyn Segmenter>, text: T) -> Self {
+        Self {
+            segmenter,
+            text: text.into(),
+            spans: OnceLock::new(),
+            index: OnceLock::new(),
+            offsets: OnceLock::new(),
+        }
+    }
+
+    /// Creates a source with already located spans of sentences
+    pub fn with_spans(segmenter: Box<dyn Segmenter>, text: &'a str, spans: Spans) -> Self {
+        Self {
+            segmenter,
+            text: text.into(),
+            spans: OnceLock::from(spans),
+            index: OnceLock::new(),
+            offsets: OnceLock::new(),
+        }
+    }
+
+    /// Returns the paragraph by its `index` or `None` if it is out of range
+    pub fn paragraph(self: &Rc<Self>, index: ParagraphId) -> Option<Paragraph<'a>> {
+        match c_uint::from(index) < self.segmenter.book_info().paragraphes {
+            true => Some(Paragraph::new(Rc::clone(self), index)),
+            false => None,
+        }
+    }
+
+    /// Returns the sentence by its `index` or `None` if it is out of range
+    pub fn sentence(self: &Rc<Self>, index: SentenceId) -> Option<Sentence<'a>> {
+        match c_uint::from(index) < self.segmenter.book_info().sentences {
+            true => Some(Sentence::new(Rc::clone(self), index)),
+            false => None,
+        }
+    }
+
+    pub fn spans(&self) -> &Spans {
+        self.spans.get_or_init(|| {
+            let count = self.segmenter.book_info().sentences;
+            let sentences = (0..count).map(|i| self.segmenter.sentence_text(i.into()).ok());
+            locate(&self.text, sentences)
+        })
+    }
+
+    pub fn index(&self) -> &BookIndex {
+        self.index.get_or_init(|| BookIndex::new(&*self.segmenter))
+    }
+
+    pub fn offsets(&self) -> &Offsets {
+        self.offsets.get_or_init(|| Offsets::new(self))
+    }
+
+    /// Returns the paragraph info from the index if it is built, from the segmenter otherwise
+    pub fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        match self.index.get() {
+            Some(book_index) => book_index.paragraph_info(index).unwrap_or_default(),
+            None => self.segmenter.paragraph_info(index),
+        }
+    }
+
+    /// Returns the sentence info from the index if it is built, from the segmenter otherwise
+    pub fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        match self.index.get() {
+            Some(book_index) => book_index.sentence_info(index).unwrap_or_default(),
+            None => self.segmenter.sentence_info(index),
+        }
+    }
+
+    pub fn sentence_span(&self, index: SentenceId) -> Option<Range<usize>> {
+        self.spans()
+            .get(c_uint::from(index) as usize)
+            .cloned()
+            .flatten()
+    }
+
+    /// Returns byte range between the previous found sentence (or the start of the text)
+    /// and the sentence, `None` if the sentence is not found
+    pub fn gap_before(&self, index: SentenceId) -> Option<Range<usize>> {
+        let i = c_uint::from(index) as usize;
+        let spans = self.spans();
+        let start = spans.get(i)?.as_ref()?.start;
+        let end_before = spans[..i]
+            .iter()
+            .rev()
+            .flatten()
+            .next()
+            .map_or(0, |s| s.end);
+        Some(end_before..start)
+    }
+
+    /// Returns byte range between the sentence and the next found one (or the end of the
+    /// text), `None` if the sentence is not found
+    pub fn gap_after(&self, index: SentenceId) -> Option<Range<usize>> {
+        let i = c_uint::from(index) as usize;
+        let spans = self.spans();
+        let end = spans.get(i)?.as_ref()?.end;
+        let start_after = spans[i + 1..]
+            .iter()
+            .flatten()
+            .next()
+            .map_or(self.text.len(), |s| s.start);
+        Some(end..start_after)
+    }
+}
+
+/// Finds byte ranges of consecutive `sentences` in `text`. A sentence that cannot
+/// be found gets `None`, the search of the next one continues from the previous match.
+pub(crate) fn locate<I: Iterator<Item = Option<String>>>(text: &str, sentences: I) -> Spans {
+    let mut cursor = 0;
+
+    sentences
+        .map(|sentence| {
+            let span = sentence.and_then(|s| find(text, cursor, &s))?;
+            cursor = span.end;
+            Some(span)
+        })
+        .collect()
+}
+
+/// Matches `sentence` in `text` at `from` position, after skipping whitespaces.
+///
+/// The native library normalizes sentences, so the match is tolerant: a whitespace
+/// matches any run of whitespaces, `...` matches `…`.
+fn find(text: &str, from: usize, sentence: &str) -> Option<Range<usize>> {
+    let rest = &text[from..];
+    let start = from + (rest.len() - rest.trim_start().len());
+
+    let mut source = text[start..].char_indices().peekable();
+    let mut pattern = sentence.chars().peekable();
+    let mut end = start;
+
+    while let Some(p) = pattern.next() {
+        let (_, c) = source.next()?;
+
+        if p.is_whitespace() && c.is_whitespace() {
+            while pattern.next_if(|p| p.is_whitespace()).is_some() {}
+            while source.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        } else if p == '.' && c == '…' {
+            // `...` in the sentence stands for `…` in the text
+            pattern.next_if_eq(&'.')?;
+            pattern.next_if_eq(&'.')?;
+        } else if p != c {
+            return None;
+        }
+
+        end = source.peek().map_or(text.len(), |(j, _)| start + j);
+    }
+
+    Some(start..end)
+}
@@ -0,0 +1,217 @@
+//! Parsing of a text read from an [`io::Read`] source chunk by chunk.
+//!
+//! Only the chunk being parsed and the unfinished paragraph after it are kept in memory,
+//! so a text of any length can be parsed, pipes included.
+
+use std::{
+    io::{self, Read},
+    mem,
+    os::raw::c_uint,
+    sync::Arc,
+};
+
+use crate::{
+    book::Book,
+    chunked::{Chunk, GlobalParagraph, GlobalSentence},
+    error::Error,
+    owned::OwnedBook,
+};
+
+/// Default size of a chunk in bytes
+const CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// Iterator over chunks of a text read from `R`, every chunk contains whole paragraphes.
+///
+/// Ids and offsets of paragraphes and sentences continue from chunk to chunk. Chunks
+/// without paragraphes are skipped.
+#[derive(Debug)]
+pub struct StreamingBook<R> {
+    reader: R,
+    chunk_size: usize,
+    /// Bytes that are read, but not parsed yet
+    buffer: Vec<u8>,
+    eof: bool,
+    offset: u64,
+    paragraphes: u64,
+    sentences: u64,
+}
+
+impl<R: Read> StreamingBook<R> {
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            reader,
+            chunk_size: CHUNK_SIZE,
+            buffer: vec![],
+            eof: false,
+            offset: 0,
+            paragraphes: 0,
+            sentences: 0,
+        }
+    }
+
+    /// Sets the minimal size of a chunk in bytes, a chunk is extended up to the end of its
+    /// last paragraph. It is limited by the maximal length the native library accepts.
+    pub fn chunk_size(mut self, bytes: usize) -> Self {
+        self.chunk_size = bytes.clamp(1, c_uint::MAX as usize);
+        self
+    }
+
+    /// Returns count of bytes in parsed chunks and chunks dropped as invalid UTF-8
+    pub fn bytes_read(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns count of paragraphes in parsed chunks
+    pub fn paragraphes_read(&self) -> u64 {
+        self.paragraphes
+    }
+
+    /// Returns count of sentences in parsed chunks
+    pub fn sentences_read(&self) -> u64 {
+        self.sentences
+    }
+
+    /// Reads the text of the next chunk, `None` at the end of the input
+    fn read_chunk(&mut self) -> Result<Option<String>, Error> {
+        let mut limit = self.chunk_size;
+        // Bytes before this position do not contain line breaks
+        let mut searched = 0;
+
+        loop {
+            if !self.eof && self.buffer.len() < limit {
+                let want = (limit - self.buffer.len()) as u64;
+                let read = (&mut self.reader)
+                    .take(want)
+                    .read_to_end(&mut self.buffer)?;
+                self.eof = (read as u64) < want;
+            }
+
+            let line_end = self.buffer[searched..]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map(|i| searched + i + 1);
+
+            let end = match line_end {
+                _ if self.eof => self.buffer.len(),
+                Some(end) => end,
+                // The paragraph is longer than a chunk
+                None if self.buffer.len() > c_uint::MAX as usize => {
+                    return Err(Error::TextTooLarge {
+                        len: self.buffer.len(),
+                    })
+                }
+                None => {
+                    searched = self.buffer.len();
+                    limit = self.buffer.len().saturating_add(self.chunk_size);
+                    continue;
+                }
+            };
+
+            if end == 0 {
+                return Ok(None);
+            }
+
+            let rest = self.buffer.split_off(end);
+            let text = mem::replace(&mut self.buffer, rest);
+            return match String::from_utf8(text) {
+                Ok(text) => Ok(Some(text)),
+                Err(e) => {
+                    // The chunk is dropped, offsets of the next chunks must still be right
+                    self.offset += e.as_bytes().len() as u64;
+                    Err(io::Error::new(io::ErrorKind::InvalidData, e).into())
+                }
+            };
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamingBook<R> {
+    type Item = Result<StreamedChunk, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let text: Arc<str> = match self.read_chunk() {
+                Ok(Some(text)) => text.into(),
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            };
+
+            let offset = self.offset;
+            self.offset += text.len() as u64;
+
+            // A chunk of whitespaces only has nothing to parse
+            let book = match Book::from_utf8(&text) {
+                Err(Error::EmptyInput) => continue,
+                book => book.and_then(|book| book.into_owned(Arc::clone(&text))),
+            };
+
+            return Some(book.map(|book| {
+                let info = book.info();
+                let chunk = StreamedChunk {
+                    offset,
+                    first_paragraph: self.paragraphes,
+                    first_sentence: self.sentences,
+                    book,
+                };
+                self.paragraphes += u64::from(info.paragraphes);
+                self.sentences += u64::from(info.sentences);
+                chunk
+            }));
+        }
+    }
+}
+
+/// Chunk of [`StreamingBook`] that owns its text and parsed data
+#[derive(Debug, Clone)]
+pub struct StreamedChunk {
+    offset: u64,
+    first_paragraph: u64,
+    first_sentence: u64,
+    book: OwnedBook,
+}
+
+impl StreamedChunk {
+    /// Returns byte offset of the chunk in the whole text
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns the global id of the first paragraph of the chunk
+    pub fn first_paragraph(&self) -> u64 {
+        self.first_paragraph
+    }
+
+    /// Returns the global id of the first sentence of the chunk
+    pub fn first_sentence(&self) -> u64 {
+        self.first_sentence
+    }
+
+    /// Returns the chunk as a book, its ids and offsets are local to the chunk
+    pub fn book(&self) -> &OwnedBook {
+        &self.book
+    }
+
+    /// Returns the chunk with the same API as chunks of [`crate::ChunkedBook`] have
+    pub fn chunk(&self) -> Chunk<'_> {
+        Chunk::new(
+            self.offset,
+            self.first_paragraph,
+            self.first_sentence,
+            self.book.book(),
+        )
+    }
+
+    /// Returns paragraphes of the chunk with global ids and offsets
+    pub fn paragraphes(&self) -> impl Iterator<Item = GlobalParagraph<'_>> + '_ {
+        let chunk = self.chunk();
+        let paragraphes = chunk.book().paragraphes();
+        paragraphes.map(move |p| chunk.global_paragraph(p))
+    }
+
+    /// Returns sentences of the chunk with global ids and offsets
+    pub fn sentences(&self) -> impl Iterator<Item = GlobalSentence<'_>> + '_ {
+        let chunk = self.chunk();
+        let sentences = chunk.book().sentences();
+        sentences.map(move |s| chunk.global_sentence(s))
+    }
+}
@@ -0,0 +1,101 @@
+//! Serialisable tree of a whole book: paragraphes with their sentences.
+
+use std::{os::raw::c_uint, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ffi::{BookInfo, ParagraphInfo, SentenceInfo},
+    memory::MemorySegmenter,
+    owned::OwnedBook,
+    source::locate,
+};
+
+/// Plain representation of a parsed book, it converts to and from [`OwnedBook`]
+/// without calling the native library.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BookTree {
+    pub info: BookInfo,
+    /// Original text of the book
+    pub text: String,
+    pub paragraphes: Vec<ParagraphNode>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParagraphNode {
+    pub info: ParagraphInfo,
+    pub text: String,
+    pub sentences: Vec<SentenceNode>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SentenceNode {
+    pub info: SentenceInfo,
+    pub text: String,
+}
+
+impl From<&OwnedBook> for BookTree {
+    fn from(book: &OwnedBook) -> Self {
+        let data = book.segmenter();
+        let sentences = data.sentences();
+
+        let paragraphes = data
+            .paragraphes()
+            .iter()
+            .map(|(info, text)| {
+                let first = c_uint::from(info.sentence_first) as usize;
+                let last = first.saturating_add(info.sentences as usize);
+                let sentences = sentences
+                    .get(first..last.min(sentences.len()))
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|(info, text)| SentenceNode {
+                        info: *info,
+                        text: text.clone(),
+                    })
+                    .collect();
+
+                ParagraphNode {
+                    info: *info,
+                    text: text.clone(),
+                    sentences,
+                }
+            })
+            .collect();
+
+        Self {
+            info: book.info(),
+            text: String::from(book.text()),
+            paragraphes,
+        }
+    }
+}
+
+impl From<OwnedBook> for BookTree {
+    fn from(book: OwnedBook) -> Self {
+        Self::from(&book)
+    }
+}
+
+impl From<BookTree> for OwnedBook {
+    fn from(tree: BookTree) -> Self {
+        let mut paragraphes = Vec::with_capacity(tree.paragraphes.len());
+        let mut sentences = vec![];
+
+        for paragraph in tree.paragraphes {
+            sentences.extend(paragraph.sentences.into_iter().map(|s| (s.info, s.text)));
+            paragraphes.push((paragraph.info, paragraph.text));
+        }
+
+        let spans = locate(&tree.text, sentences.iter().map(|s| Some(s.1.clone())));
+        let data = MemorySegmenter::new(tree.info, paragraphes, sentences);
+        OwnedBook::new(data, Arc::from(tree.text), spans)
+    }
+}
+
+impl OwnedBook {
+    /// Returns the serialisable tree of the book
+    pub fn tree(&self) -> BookTree {
+        BookTree::from(self)
+    }
+}
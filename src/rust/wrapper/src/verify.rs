@@ -0,0 +1,294 @@
+//! Consistency checks of a parsed book, see [`crate::Book::verify`].
+
+use std::{fmt::Display, os::raw::c_uint};
+
+use crate::{
+    ffi::{BookInfo, ParagraphId, SentenceId, StringSize},
+    index::BookIndex,
+};
+
+/// A contradiction between infos reported by the segmenter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Violation {
+    /// The paragraph reports another index than its position
+    ParagraphIndex {
+        index: ParagraphId,
+        reported: ParagraphId,
+    },
+    /// The sentence reports another index than its position
+    SentenceIndex {
+        index: SentenceId,
+        reported: SentenceId,
+    },
+    /// Sentences of the paragraph do not start right after sentences of the previous one
+    ParagraphNotContiguous {
+        index: ParagraphId,
+        sentence_first: SentenceId,
+        expected: SentenceId,
+    },
+    /// Paragraphes do not cover exactly all sentences of the book
+    SentencesNotCovered { covered: u32, sentences: u32 },
+    /// The sentence reports a paragraph that does not contain it
+    WrongParagraph {
+        index: SentenceId,
+        p_index: ParagraphId,
+        expected: Option<ParagraphId>,
+    },
+    /// The sentence reports another number than its position in the paragraph
+    WrongNumber {
+        index: SentenceId,
+        s_number: u32,
+        expected: u32,
+    },
+    /// The paragraph size is not the size of its sentences joined with a space
+    ParagraphSize {
+        index: ParagraphId,
+        size: StringSize,
+        expected: StringSize,
+    },
+    /// The book size is not the size of its paragraphes joined with a line break
+    BookSize {
+        size: StringSize,
+        expected: StringSize,
+    },
+    /// Sentences of the paragraph joined with a space are longer than a size can be
+    ParagraphSizeOverflow {
+        index: ParagraphId,
+        size: StringSize,
+    },
+    /// Paragraphes joined with a line break are longer than a size can be
+    BookSizeOverflow { size: StringSize },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::ParagraphIndex { index, reported } => {
+                write!(f, "paragraph {} reports index {}", index, reported)
+            }
+            Violation::SentenceIndex { index, reported } => {
+                write!(f, "sentence {} reports index {}", index, reported)
+            }
+            Violation::ParagraphNotContiguous {
+                index,
+                sentence_first,
+                expected,
+            } => write!(
+                f,
+                "paragraph {} starts at sentence {} instead of {}",
+                index, sentence_first, expected
+            ),
+            Violation::SentencesNotCovered { covered, sentences } => write!(
+                f,
+                "paragraphes cover {} sentences, the book has {}",
+                covered, sentences
+            ),
+            Violation::WrongParagraph {
+                index,
+                p_index,
+                expected: Some(expected),
+            } => write!(
+                f,
+                "sentence {} reports paragraph {} instead of {}",
+                index, p_index, expected
+            ),
+            Violation::WrongParagraph {
+                index,
+                p_index,
+                expected: None,
+            } => write!(
+                f,
+                "sentence {} reports paragraph {}, but no paragraph contains it",
+                index, p_index
+            ),
+            Violation::WrongNumber {
+                index,
+                s_number,
+                expected,
+            } => write!(
+                f,
+                "sentence {} reports number {} instead of {}",
+                index, s_number, expected
+            ),
+            Violation::ParagraphSize {
+                index,
+                size,
+                expected,
+            } => write!(
+                f,
+                "paragraph {} has size {}/{} (bytes/symbols), its sentences take {}/{}",
+                index, size.bytes, size.symbols, expected.bytes, expected.symbols
+            ),
+            Violation::BookSize { size, expected } => write!(
+                f,
+                "book has size {}/{} (bytes/symbols), its paragraphes take {}/{}",
+                size.bytes, size.symbols, expected.bytes, expected.symbols
+            ),
+            Violation::ParagraphSizeOverflow { index, size } => write!(
+                f,
+                "paragraph {} has size {}/{} (bytes/symbols), its sentences take more than {}",
+                index,
+                size.bytes,
+                size.symbols,
+                c_uint::MAX
+            ),
+            Violation::BookSizeOverflow { size } => write!(
+                f,
+                "book has size {}/{} (bytes/symbols), its paragraphes take more than {}",
+                size.bytes,
+                size.symbols,
+                c_uint::MAX
+            ),
+        }
+    }
+}
+
+/// Result of [`crate::Book::verify`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub violations: Vec<Violation>,
+}
+
+impl Report {
+    /// Returns `true` if no violations are found
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub(crate) fn new(info: &BookInfo, index: &BookIndex) -> Self {
+        let mut violations = vec![];
+        let paragraphes = index.paragraphes();
+        let sentences = index.sentences();
+
+        // Paragraph and its number in it for every sentence, by paragraph ranges
+        let mut owners = vec![None; sentences.len()];
+        let mut next_first: c_uint = 0;
+
+        for (i, paragraph) in paragraphes.iter().enumerate() {
+            let id = ParagraphId::from(i as c_uint);
+            if paragraph.index != id {
+                violations.push(Violation::ParagraphIndex {
+                    index: id,
+                    reported: paragraph.index,
+                });
+            }
+
+            let first = c_uint::from(paragraph.sentence_first);
+            if first != next_first {
+                violations.push(Violation::ParagraphNotContiguous {
+                    index: id,
+                    sentence_first: paragraph.sentence_first,
+                    expected: next_first.into(),
+                });
+            }
+            next_first = first.saturating_add(paragraph.sentences);
+
+            let range = first as usize..(next_first as usize).min(sentences.len());
+            for (number, owner) in owners
+                .get_mut(range)
+                .unwrap_or_default()
+                .iter_mut()
+                .enumerate()
+            {
+                owner.get_or_insert((id, number as c_uint));
+            }
+
+            let expected = index
+                .checked_sentences_size(paragraph.sentence_first..next_first.into())
+                .and_then(|size| with_separators(size, paragraph.sentences));
+            match expected {
+                Some(expected) if paragraph.size != expected => {
+                    violations.push(Violation::ParagraphSize {
+                        index: id,
+                        size: paragraph.size,
+                        expected,
+                    })
+                }
+                Some(_) => (),
+                None => violations.push(Violation::ParagraphSizeOverflow {
+                    index: id,
+                    size: paragraph.size,
+                }),
+            }
+        }
+
+        if next_first != info.sentences {
+            violations.push(Violation::SentencesNotCovered {
+                covered: next_first,
+                sentences: info.sentences,
+            });
+        }
+
+        for (i, (sentence, owner)) in sentences.iter().zip(owners).enumerate() {
+            let id = SentenceId::from(i as c_uint);
+            if sentence.index != id {
+                violations.push(Violation::SentenceIndex {
+                    index: id,
+                    reported: sentence.index,
+                });
+            }
+
+            match owner {
+                Some((p_index, _)) if p_index != sentence.p_index => {
+                    violations.push(Violation::WrongParagraph {
+                        index: id,
+                        p_index: sentence.p_index,
+                        expected: Some(p_index),
+                    })
+                }
+                Some((_, number)) if number != sentence.s_number => {
+                    violations.push(Violation::WrongNumber {
+                        index: id,
+                        s_number: sentence.s_number,
+                        expected: number,
+                    })
+                }
+                Some(_) => (),
+                None => violations.push(Violation::WrongParagraph {
+                    index: id,
+                    p_index: sentence.p_index,
+                    expected: None,
+                }),
+            }
+        }
+
+        let expected = index
+            .checked_paragraphes_size(0.into()..info.paragraphes.into())
+            .and_then(|size| with_separators(size, info.paragraphes));
+        match expected {
+            Some(expected) if info.size != expected => violations.push(Violation::BookSize {
+                size: info.size,
+                expected,
+            }),
+            Some(_) => (),
+            None => violations.push(Violation::BookSizeOverflow { size: info.size }),
+        }
+
+        Self { violations }
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.violations.len() {
+            0 => write!(f, "no violations"),
+            len => {
+                write!(f, "{} violations:", len)?;
+                for violation in &self.violations {
+                    write!(f, "\n  {}", violation)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Adds one-symbol separators between `count` items of total `size`, `None` if the
+/// result does not fit `c_uint`
+fn with_separators(size: StringSize, count: c_uint) -> Option<StringSize> {
+    let separators = count.saturating_sub(1);
+    Some(StringSize {
+        bytes: size.bytes.checked_add(separators)?,
+        symbols: size.symbols.checked_add(separators)?,
+    })
+}
@@ -1,173 +1,216 @@
-use libloading::{Library, Symbol};
 use std::{
-    error::Error,
-    fmt::Display,
+    convert::TryFrom,
     os::raw::{c_uchar, c_uint},
+    ptr, slice,
 };
 
-use crate::ffi::*;
+use crate::{error::Error, ffi::*, library::BookLibrary, segmenter::Segmenter};
 
+/// Extra bytes after a text buffer to catch writes past the advertised size
+const GUARD: usize = 16;
+
+/// Fills a text buffer before the native library writes into it, UTF-8 never contains it
+const CANARY: u8 = 0xFF;
+
+/// [`Segmenter`] over a book parsed by the native library.
+///
+/// Texts are written by the native library into guarded buffers, their length and count
+/// of symbols are checked against the reported sizes.
 #[derive(Debug)]
 pub struct Wrapper {
-    lib: BookLibrary,
+    lib: &'static BookLibrary,
     pointer: BookRaw,
+    paranoid: bool,
 }
 
 impl Wrapper {
-    pub fn from_utf8(text: &str) -> Result<Self, Box<dyn Error>> {
-        let lib = BookLibrary::new()?;
-        let pointer = lib.from_utf8(text.as_ptr(), text.len() as c_uint);
+    pub fn from_utf8(text: &str) -> Result<Self, Error> {
+        if text.is_empty() {
+            return Err(Error::EmptyInput);
+        }
+
+        let len =
+            c_uint::try_from(text.len()).map_err(|_| Error::TextTooLarge { len: text.len() })?;
+        let lib = BookLibrary::shared()?;
+        let pointer = lib.parse_utf8(text.as_ptr(), len);
 
         match pointer.is_null() {
-            true => Err(Box::new(BookError)),
-            false => Ok(Self { lib, pointer }),
+            true => Err(Error::NullBook),
+            false => Ok(Self {
+                lib,
+                pointer,
+                paranoid: false,
+            }),
         }
     }
 
-    pub fn book_info(&self) -> BookInfo {
-        self.lib.book_info(self.pointer)
+    /// Enables cross-checks of infos before every text is read: indexes must match their
+    /// positions and sentences must belong to the paragraphes that report them.
+    /// Violations are returned as [`Error::InconsistentInfo`].
+    pub fn paranoid(mut self) -> Self {
+        self.paranoid = true;
+        self
     }
 
-    pub fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
-        self.lib.paragraph_info(self.pointer, index)
-    }
+    fn check_paragraph(&self, index: ParagraphId, info: &ParagraphInfo) -> Result<(), Error> {
+        let sentences = self.book_info().sentences;
+        let end = c_uint::from(info.sentence_first).checked_add(info.sentences);
 
-    pub fn paragraph_text(&self, index: ParagraphId) -> Option<String> {
-        let pi = self.paragraph_info(index);
-        let mut buff: Vec<u8> = Vec::with_capacity(pi.size.bytes as usize);
-        unsafe {
-            let p_buff = buff.as_mut_ptr();
-            self.lib.paragraph_text(self.pointer, index, p_buff);
-            buff.set_len(buff.capacity());
+        if info.index != index {
+            inconsistent(format!("paragraph {} reports index {}", index, info.index))
+        } else if end.filter(|&end| end <= sentences).is_none() {
+            inconsistent(format!(
+                "paragraph {} reports sentences {}+{}, the book has {}",
+                index, info.sentence_first, info.sentences, sentences
+            ))
+        } else {
+            Ok(())
         }
-        String::from_utf8(buff).ok()
     }
 
-    pub fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
-        self.lib.sentence_info(self.pointer, index)
-    }
+    fn check_sentence(&self, index: SentenceId, info: &SentenceInfo) -> Result<(), Error> {
+        let paragraphes = self.book_info().paragraphes;
+        if info.index != index {
+            return inconsistent(format!("sentence {} reports index {}", index, info.index));
+        }
+        if c_uint::from(info.p_index) >= paragraphes {
+            return inconsistent(format!(
+                "sentence {} reports paragraph {}, the book has {}",
+                index, info.p_index, paragraphes
+            ));
+        }
 
-    pub fn sentence_text(&self, index: SentenceId) -> Option<String> {
-        let pi = self.sentence_info(index);
-        let mut buff: Vec<u8> = Vec::with_capacity(pi.size.bytes as usize);
-        unsafe {
-            let p_buff = buff.as_mut_ptr();
-            self.lib.sentence_text(self.pointer, index, p_buff);
-            buff.set_len(buff.capacity());
+        let paragraph = self.paragraph_info(info.p_index);
+        let number = c_uint::from(index).checked_sub(paragraph.sentence_first.into());
+        match number {
+            Some(number) if number < paragraph.sentences && number == info.s_number => Ok(()),
+            _ => inconsistent(format!(
+                "sentence {} (number {}) is not in paragraph {} (sentences {}+{})",
+                index, info.s_number, info.p_index, paragraph.sentence_first, paragraph.sentences
+            )),
         }
-        String::from_utf8(buff).ok()
     }
 }
 
-impl Drop for Wrapper {
-    fn drop(&mut self) {
-        self.lib.dispose(self.pointer)
-    }
+fn inconsistent(reason: String) -> Result<(), Error> {
+    Err(Error::InconsistentInfo { reason })
 }
 
-#[derive(Debug)]
-struct BookLibrary(Library);
-
-impl BookLibrary {
-    fn new() -> Result<Self, Box<dyn Error>> {
-        let this = Self(Library::new("book_parse")?); // .dll \ .so \ .dylib
-
-        unsafe {
-            this.0.get::<unsafe extern "C" fn()>(b"from_utf8")?;
-            this.0.get::<unsafe extern "C" fn()>(b"dispose")?;
-            this.0.get::<unsafe extern "C" fn()>(b"paragraph_info")?;
-            this.0.get::<unsafe extern "C" fn()>(b"paragraph_text")?;
-            this.0.get::<unsafe extern "C" fn()>(b"sentence_info")?;
-            this.0.get::<unsafe extern "C" fn()>(b"sentence_text")?;
-        }
-
-        Ok(this)
+impl Segmenter for Wrapper {
+    fn book_info(&self) -> BookInfo {
+        self.lib.book_info(self.pointer)
     }
 
-    fn from_utf8(&self, pointer: *const c_uchar, len: c_uint) -> BookRaw {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(pointer: *const c_uchar, len: c_uint) -> BookRaw>>(
-                    b"from_utf8",
-                )
-                .unwrap()(pointer, len)
+    fn paragraph_info(&self, index: ParagraphId) -> ParagraphInfo {
+        // The native library does not check the index
+        if c_uint::from(index) >= self.book_info().paragraphes {
+            return ParagraphInfo::default();
         }
+        self.lib.paragraph_info(self.pointer, index)
     }
 
-    fn dispose(&self, book: BookRaw) {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(BookRaw)>>(b"dispose")
-                .unwrap()(book)
-        }
+    fn paragraph_text(&self, index: ParagraphId) -> Result<String, Error> {
+        let mut text = String::new();
+        self.write_paragraph_text(index, &mut text)?;
+        Ok(text)
     }
 
-    fn book_info(&self, book: BookRaw) -> BookInfo {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(BookRaw) -> BookInfo>>(b"book_info")
-                .unwrap()(book)
+    fn sentence_info(&self, index: SentenceId) -> SentenceInfo {
+        if c_uint::from(index) >= self.book_info().sentences {
+            return SentenceInfo::default();
         }
+        self.lib.sentence_info(self.pointer, index)
     }
 
-    fn paragraph_info(&self, book: BookRaw, index: ParagraphId) -> ParagraphInfo {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(BookRaw, ParagraphId) -> ParagraphInfo>>(
-                    b"paragraph_info",
-                )
-                .unwrap()(book, index)
-        }
+    fn sentence_text(&self, index: SentenceId) -> Result<String, Error> {
+        let mut text = String::new();
+        self.write_sentence_text(index, &mut text)?;
+        Ok(text)
     }
 
-    unsafe fn paragraph_text(&self, book: BookRaw, index: ParagraphId, buff: *mut c_uchar) {
-        self.0
-            .get::<Symbol<unsafe extern "C" fn(BookRaw, ParagraphId, *mut c_uchar)>>(
-                b"paragraph_text",
-            )
-            .unwrap()(book, index, buff)
-    }
+    fn write_paragraph_text(&self, index: ParagraphId, buf: &mut String) -> Result<(), Error> {
+        let len = self.book_info().paragraphes;
+        if c_uint::from(index) >= len {
+            return Err(Error::ParagraphOutOfRange { index, len });
+        }
 
-    fn sentence_info(&self, book: BookRaw, index: SentenceId) -> SentenceInfo {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(BookRaw, SentenceId) -> SentenceInfo>>(
-                    b"sentence_info",
-                )
-                .unwrap()(book, index)
+        let info = self.paragraph_info(index);
+        if self.paranoid {
+            self.check_paragraph(index, &info)?;
         }
+
+        append(buf, info.size, |p_buff| unsafe {
+            self.lib.paragraph_text(self.pointer, index, p_buff)
+        })
     }
 
-    fn sentence_text(&self, book: BookRaw, index: SentenceId, buff: *mut c_uchar) {
-        unsafe {
-            self.0
-                .get::<Symbol<unsafe extern "C" fn(BookRaw, SentenceId, *mut c_uchar)>>(
-                    b"sentence_text",
-                )
-                .unwrap()(book, index, buff)
+    fn write_sentence_text(&self, index: SentenceId, buf: &mut String) -> Result<(), Error> {
+        let len = self.book_info().sentences;
+        if c_uint::from(index) >= len {
+            return Err(Error::SentenceOutOfRange { index, len });
         }
-    }
-}
 
-#[derive(Debug)]
-pub struct BookError;
+        let info = self.sentence_info(index);
+        if self.paranoid {
+            self.check_sentence(index, &info)?;
+        }
 
-impl Display for BookError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "BookError occured")
+        append(buf, info.size, |p_buff| unsafe {
+            self.lib.sentence_text(self.pointer, index, p_buff)
+        })
     }
 }
 
-impl Error for BookError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
-    }
+/// Appends the text written by `write` to `buf`. The text must take exactly `size`,
+/// `buf` is left unchanged otherwise.
+fn append<F: FnOnce(*mut c_uchar)>(
+    buf: &mut String,
+    size: StringSize,
+    write: F,
+) -> Result<(), Error> {
+    let start = buf.len();
+    let expected = size.bytes as usize;
+    buf.reserve(expected + GUARD);
+
+    // The length of `buf` is changed only after the bytes are validated
+    let bytes = unsafe { buf.as_mut_vec() };
+    let written = unsafe {
+        let p_buff = bytes.as_mut_ptr().add(start);
+        ptr::write_bytes(p_buff, CANARY, expected + GUARD);
+        write(p_buff);
+        slice::from_raw_parts(p_buff, expected + GUARD)
+    };
+
+    let (text, guard) = written.split_at(expected);
+    if guard.iter().any(|&b| b != CANARY) {
+        return Err(Error::LongWrite { expected });
+    }
+    if text.last() == Some(&CANARY) {
+        let written = text.iter().rposition(|&b| b != CANARY).map_or(0, |i| i + 1);
+        return Err(Error::ShortWrite { expected, written });
+    }
+
+    let text = std::str::from_utf8(text).map_err(Error::InvalidUtf8)?;
+    let symbols = text.chars().count();
+    if symbols != size.symbols as usize {
+        return Err(Error::SymbolsMismatch {
+            expected: size.symbols,
+            actual: symbols,
+        });
+    }
+
+    unsafe { bytes.set_len(start + expected) };
+    Ok(())
+}
 
-    fn description(&self) -> &str {
-        "BookError occured"
-    }
+// The handle is owned by the wrapper and disposed once on drop. It assumes that the
+// native library does not bind a book to the thread that parsed it, so the wrapper
+// may be moved to another thread. Calls with the same book are never concurrent, the
+// wrapper is not `Sync`.
+unsafe impl Send for Wrapper {}
 
-    fn cause(&self) -> Option<&dyn Error> {
-        None
+impl Drop for Wrapper {
+    fn drop(&mut self) {
+        self.lib.dispose(self.pointer)
     }
 }
@@ -0,0 +1,82 @@
+//! Compares the pure Rust segmentation with the native library on a shared corpus.
+//! The tests are skipped if the native library cannot be loaded.
+
+#![cfg(feature = "rust-segmenter")]
+
+use std::{fs, path::Path};
+
+use book_parse_wrapper::{Book, Error};
+
+fn check(name: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("corpus")
+        .join(name);
+    let text = fs::read_to_string(&path).unwrap();
+
+    let native = match Book::from_utf8(&text) {
+        Err(Error::LibraryNotFound { .. }) => {
+            eprintln!("native library is not found, `{}` is skipped", name);
+            return;
+        }
+        result => result.unwrap(),
+    };
+    let rust = Book::from_utf8_rust(&text).unwrap();
+
+    assert_eq!(native.info(), rust.info(), "{}: book info", name);
+    assert!(native.verify().is_ok(), "{}: {}", name, native.verify());
+    assert!(rust.verify().is_ok(), "{}: {}", name, rust.verify());
+    assert!(
+        native.reconstruct() == rust.reconstruct(),
+        "{}: reconstructed text",
+        name
+    );
+    assert!(
+        native.sentences().all(|s| s.span().is_some()),
+        "{}: spans",
+        name
+    );
+
+    for (n, r) in native.paragraphes().zip(rust.paragraphes()) {
+        assert_eq!(n.info(), r.info(), "{}: paragraph info", name);
+        assert_eq!(n.text(), r.text(), "{}: paragraph text", name);
+    }
+
+    for (n, r) in native.sentences().zip(rust.sentences()) {
+        assert_eq!(n.info(), r.info(), "{}: sentence info", name);
+        assert_eq!(n.text(), r.text(), "{}: sentence text", name);
+    }
+}
+
+/// Sentences are normalized by the native library, the gaps are kept as they are
+#[test]
+fn reconstruct_normalized() {
+    let text = "Wait…  what?\n\n  Next   one.\tOk.";
+    match Book::from_utf8(text) {
+        Err(Error::LibraryNotFound { .. }) => eprintln!("native library is not found"),
+        result => assert_eq!(
+            result.unwrap().reconstruct(),
+            "Wait... what?\n\n  Next one.\tOk."
+        ),
+    }
+}
+
+#[test]
+fn english() {
+    check("english.txt");
+}
+
+#[test]
+fn russian() {
+    check("russian.txt");
+}
+
+#[test]
+fn layout() {
+    check("layout.txt");
+}
+
+#[test]
+fn punctuation() {
+    check("punctuation.txt");
+}
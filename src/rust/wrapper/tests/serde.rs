@@ -0,0 +1,51 @@
+//! Round-trips books through their serialisable tree.
+
+#![cfg(all(feature = "serde", feature = "rust-segmenter"))]
+
+use book_parse_wrapper::{Book, BookTree, OwnedBook, SentenceId};
+
+const TEXT: &str = "First one. Second one!\n\n  Mr. Smith  went home.";
+
+#[test]
+fn tree() {
+    let book = Book::from_utf8_rust(TEXT).unwrap().materialize().unwrap();
+    let tree = book.tree();
+
+    assert_eq!(tree.text, TEXT);
+    assert_eq!(tree.paragraphes.len(), 2);
+    assert_eq!(tree.paragraphes[1].text, "Mr. Smith went home.");
+
+    let sentences = tree
+        .paragraphes
+        .iter()
+        .flat_map(|p| p.sentences.iter().map(|s| s.text.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        sentences,
+        ["First one.", "Second one!", "Mr. Smith went home."]
+    );
+}
+
+#[test]
+fn round_trip() {
+    let book = Book::from_utf8_rust(TEXT).unwrap().materialize().unwrap();
+
+    let json = serde_json::to_string(&book).unwrap();
+    let restored: OwnedBook = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.info(), book.info());
+    assert_eq!(restored.tree(), book.tree());
+
+    let sentence = restored.sentence(SentenceId::from(2)).unwrap();
+    assert_eq!(sentence.text().unwrap(), "Mr. Smith went home.");
+    assert_eq!(sentence.text_ref(), Some("Mr. Smith  went home."));
+}
+
+#[test]
+fn ids_are_plain_numbers() {
+    let book = Book::from_utf8_rust("One.").unwrap().materialize().unwrap();
+    let json = serde_json::to_value(BookTree::from(&book)).unwrap();
+
+    assert_eq!(json["paragraphes"][0]["info"]["index"], 0);
+    assert_eq!(json["paragraphes"][0]["sentences"][0]["info"]["p_index"], 0);
+}
@@ -0,0 +1,71 @@
+//! Smoke tests of separate books parsed in several threads at once and of a wrapper
+//! moved to another thread, as the chunked parser does. They cannot prove the absence
+//! of data races. The tests are skipped if the native library cannot be loaded.
+
+use std::thread;
+
+use book_parse_wrapper::{Book, Error, Segmenter, Wrapper};
+
+const TEXT: &str = "One. Two!\n\n  \nThree? Four\nFive.\n\nSix. Seven.";
+
+/// Returns infos and texts of all sentences
+fn sentences(wrapper: &Wrapper) -> Vec<String> {
+    let book = wrapper.book_info();
+    (0..book.sentences)
+        .map(|i| {
+            let info = wrapper.sentence_info(i.into());
+            let text = wrapper.sentence_text(i.into()).unwrap();
+            format!("{:?} {}", info, text)
+        })
+        .collect()
+}
+
+fn parse(text: &str) -> Option<Wrapper> {
+    match Wrapper::from_utf8(text) {
+        Err(Error::LibraryNotFound { .. }) => {
+            eprintln!("native library is not found, the test is skipped");
+            None
+        }
+        result => Some(result.unwrap()),
+    }
+}
+
+#[test]
+fn parse_in_threads() {
+    let expected = match parse(TEXT) {
+        Some(wrapper) => sentences(&wrapper),
+        None => return,
+    };
+
+    let threads = (0..8)
+        .map(|_| {
+            thread::spawn(|| {
+                (0..10)
+                    .map(|_| sentences(&parse(TEXT).unwrap()))
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        for parsed in thread.join().unwrap() {
+            assert_eq!(parsed, expected);
+        }
+    }
+}
+
+#[test]
+fn move_to_thread() {
+    let wrapper = match parse(TEXT) {
+        Some(wrapper) => wrapper,
+        None => return,
+    };
+    let expected = sentences(&wrapper);
+
+    let parsed = thread::spawn(move || sentences(&wrapper)).join().unwrap();
+    assert_eq!(parsed, expected);
+    assert_eq!(
+        Book::from_utf8(TEXT).unwrap().sentences_len(),
+        expected.len()
+    );
+}